@@ -0,0 +1,87 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use langram::earley::EarleyParser;
+use langram::{CFGrammar, FromStr, Parser, XorShiftRng};
+
+/// System allocator keeping track of the peak number of allocated bytes.
+struct PeakAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(allocated, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAlloc = PeakAlloc;
+
+fn main() {
+    let grammar = CFGrammar::from_str("ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE")
+        .expect("Failed to parse the grammar.");
+    let mut parser = EarleyParser::new();
+    parser.fit(&grammar).expect("Failed to fit the parser");
+
+    for depth in [50, 100, 200, 400] {
+        let word = format!("{}a{}", "(a+".repeat(depth), ")".repeat(depth));
+        let start = Instant::now();
+        assert!(parser.predict(&word));
+        println!("nested depth {depth}: {:?}", start.elapsed());
+    }
+
+    let mut rng = XorShiftRng::new(42);
+    let words: Vec<_> = std::iter::repeat_with(|| grammar.sample(&mut rng, 8))
+        .flatten()
+        .take(10_000)
+        .collect();
+    let words: Vec<_> = words.iter().map(String::as_str).collect();
+    let start = Instant::now();
+
+    for word in words.iter() {
+        assert!(parser.predict(word));
+    }
+
+    println!(
+        "{} short words one by one: {:?}",
+        words.len(),
+        start.elapsed()
+    );
+    let start = Instant::now();
+    assert!(parser
+        .predict_many(&words)
+        .into_iter()
+        .all(|accepted| accepted));
+    println!(
+        "{} short words in a batch: {:?}",
+        words.len(),
+        start.elapsed()
+    );
+
+    let word = format!("{}a", "a+a*(a+a)+".repeat(5_000));
+
+    for gc in [false, true] {
+        let mut parser = EarleyParser::with_gc(gc);
+        parser.fit(&grammar).expect("Failed to fit the parser");
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+        let start = Instant::now();
+        assert!(parser.predict(&word));
+        println!(
+            "expression of {} letters with gc {gc}: {:?}, peak {} KiB",
+            word.len(),
+            start.elapsed(),
+            (PEAK.load(Ordering::Relaxed) - before) / 1024
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::time::Instant;
+
+use langram::lr1::lalr::LALR1Parser;
+use langram::lr1::slr::SLRParser;
+use langram::lr1::LR1Parser;
+use langram::{CFGrammar, FromStr, Parser};
+
+fn main() {
+    let grammars = [
+        (
+            "expressions",
+            "ETFUP\na+\\-*/^()n\nE->E+T\nE->E-T\nE->T\nT->T*U\nT->T/U\nT->U\nU->-U\nU->F\n\
+             F->P^F\nF->P\nP->(E)\nP->a\nP->n\nE",
+        ),
+        (
+            "statements",
+            "SLCEA\n;={}iwxy()+<\nS->L\nL->L;C\nL->C\nC->x=E\nC->i(E)C\nC->w(E)C\n\
+             C->{L}\nE->E+A\nE->E<A\nE->A\nA->x\nA->y\nA->(E)\nS",
+        ),
+    ];
+
+    let levels = get_precedence_grammar();
+    let grammars = grammars
+        .iter()
+        .map(|(name, grammar)| (*name, grammar.to_string()));
+
+    for (name, grammar) in grammars.chain([("precedence levels", levels)]) {
+        let grammar = CFGrammar::from_str(&grammar).expect("Failed to parse the grammar.");
+
+        let start = Instant::now();
+        LR1Parser::new()
+            .fit(&grammar)
+            .expect("Failed to fit the parser");
+        println!("LR(1) {name}: {:?}", start.elapsed());
+
+        let start = Instant::now();
+        LALR1Parser::new()
+            .fit(&grammar)
+            .expect("Failed to fit the parser");
+        println!("LALR(1) {name}: {:?}", start.elapsed());
+
+        let start = Instant::now();
+        SLRParser::new()
+            .fit(&grammar)
+            .expect("Failed to fit the parser");
+        println!("SLR(1) {name}: {:?}", start.elapsed());
+    }
+}
+
+/// Left-associative binary operators on twelve precedence levels, whose states
+/// have large closures with many lookaheads.
+fn get_precedence_grammar() -> String {
+    let operators = "+-*/%^&<>=!~";
+    let levels: Vec<_> = ('A'..='M').collect();
+    let mut rules = Vec::new();
+
+    for (i, operator) in operators.chars().enumerate() {
+        let (level, next) = (levels[i], levels[i + 1]);
+        rules.push(format!("{level}->{level}{operator}{next}|{next}"));
+    }
+
+    rules.push(format!("{}->(A)|a", levels[levels.len() - 1]));
+    format!(
+        "{}\n{}()a\n{}\nA",
+        String::from_iter(levels),
+        operators.replace('-', "\\-"),
+        rules.join("\n")
+    )
+}
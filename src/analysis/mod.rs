@@ -0,0 +1,590 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::*;
+
+impl CFGrammar {
+    /// FIRST set of the symbol, containing `EPS_TERMINAL` if it is nullable.
+    pub fn first(&self, symbol: char) -> HashSet<char> {
+        self.first_of_string(&symbol.to_string())
+    }
+
+    /// FIRST set of the word, containing `EPS_TERMINAL` if it is nullable.
+    pub fn first_of_string(&self, s: &str) -> HashSet<char> {
+        Self::first_with(&self.get_first_sets(), s)
+    }
+
+    /// FOLLOW set of the non-terminal, containing `END_TERMINAL` if it can end a word.
+    pub fn follow(&self, symbol: char) -> HashSet<char> {
+        self.get_follow_sets().remove(&symbol).unwrap_or_default()
+    }
+
+    /// Non-terminals deriving the empty word.
+    pub fn nullable(&self) -> HashSet<char> {
+        crate::transform::get_nullable(&self.get_user_rules())
+    }
+
+    /// Check if the empty word is in the language.
+    pub fn accepts_empty(&self) -> bool {
+        self.nullable().contains(&self.get_user_start())
+    }
+
+    /// Terminals some word of the language starts with, the FIRST set of the
+    /// start without `EPS_TERMINAL`. Whether the empty word is in the language
+    /// is told by `accepts_empty`.
+    pub fn initial_terminals(&self) -> HashSet<char> {
+        let mut terminals = self.first(self.get_user_start());
+        terminals.remove(&EPS_TERMINAL);
+        terminals
+    }
+
+    /// Non-terminals that can not be reached from the start symbol.
+    pub fn unreachable_non_terminals(&self) -> HashSet<char> {
+        let mut reached = HashSet::from([self.start]);
+        let mut queue = vec![self.start];
+
+        while let Some(rule_left) = queue.pop() {
+            for rule_right in self.rules.get_vec(&rule_left).into_iter().flatten() {
+                for symbol in rule_right.chars() {
+                    if self.non_terminals.contains(&symbol) && reached.insert(symbol) {
+                        queue.push(symbol);
+                    }
+                }
+            }
+        }
+
+        self.non_terminals.difference(&reached).copied().collect()
+    }
+
+    /// Non-terminals that can not derive any word of terminals.
+    ///
+    /// If the start symbol is among them, the grammar generates the empty language.
+    pub fn unproductive_non_terminals(&self) -> HashSet<char> {
+        let mut productive = HashSet::new();
+
+        loop {
+            let mut changed = false;
+
+            for (rule_left, rule_right) in self.rules.flat_iter() {
+                if productive.contains(rule_left) {
+                    continue;
+                }
+
+                if rule_right.chars().all(|symbol| {
+                    !self.non_terminals.contains(&symbol) || productive.contains(&symbol)
+                }) {
+                    productive.insert(*rule_left);
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        self.non_terminals
+            .iter()
+            .copied()
+            .filter(|symbol| *symbol != START_RULE && !productive.contains(symbol))
+            .collect()
+    }
+
+    /// Human-readable table of the FIRST and FOLLOW sets and the nullability of
+    /// every non-terminal, followed by the unreachable and unproductive ones.
+    pub fn analysis_report(&self) -> String {
+        let first_sets = self.get_first_sets();
+        let follow_sets = self.get_follow_sets();
+        let nullable = self.nullable();
+        let mut non_terminals: Vec<_> = self.get_user_non_terminals().into_iter().collect();
+        non_terminals.sort();
+        let mut report = String::new();
+
+        for symbol in non_terminals {
+            let first = Self::first_with(&first_sets, &symbol.to_string());
+            let follow = follow_sets.get(&symbol).cloned().unwrap_or_default();
+            report.push_str(&format!(
+                "{}: FIRST = {}, FOLLOW = {}, nullable = {}\n",
+                symbol,
+                get_symbols_report(&first),
+                get_symbols_report(&follow),
+                nullable.contains(&symbol)
+            ));
+        }
+
+        report.push_str(&format!(
+            "Unreachable: {}\n",
+            get_symbols_report(&self.unreachable_non_terminals())
+        ));
+        report.push_str(&format!(
+            "Unproductive: {}\n",
+            get_symbols_report(&self.unproductive_non_terminals())
+        ));
+        report
+    }
+
+    /// Check if some non-terminal derives a word starting with itself.
+    pub fn is_left_recursive(&self) -> bool {
+        !self.left_recursive_cycles().is_empty()
+    }
+
+    /// Groups of non-terminals deriving words that start with each other.
+    pub fn left_recursive_cycles(&self) -> Vec<Vec<char>> {
+        let first_sets = self.get_first_sets();
+        let mut graph = HashMap::<char, HashSet<char>>::new();
+
+        for (rule_left, rule_right) in self.rules.flat_iter() {
+            for symbol in rule_right.chars() {
+                if !self.non_terminals.contains(&symbol) {
+                    break;
+                }
+
+                graph.entry(*rule_left).or_default().insert(symbol);
+
+                if !first_sets[&symbol].contains(&EPS_TERMINAL) {
+                    break;
+                }
+            }
+        }
+
+        get_cycles(&graph)
+    }
+
+    /// Check if some non-terminal derives itself in one or more steps, through
+    /// unit rules whose other symbols are nullable. Words derived through such
+    /// a cycle have infinitely many parse trees.
+    pub fn has_cycle(&self) -> bool {
+        let nullable = self.nullable();
+        let mut graph = HashMap::<char, HashSet<char>>::new();
+
+        for (rule_left, rule_right) in self.rules.flat_iter() {
+            let symbols: Vec<_> = rule_right.chars().collect();
+
+            for (i, symbol) in symbols.iter().enumerate() {
+                if self.non_terminals.contains(symbol)
+                    && symbols
+                        .iter()
+                        .enumerate()
+                        .all(|(j, other)| j == i || nullable.contains(other))
+                {
+                    graph.entry(*rule_left).or_default().insert(*symbol);
+                }
+            }
+        }
+
+        !get_cycles(&graph).is_empty()
+    }
+
+    pub(crate) fn get_follow_sets(&self) -> HashMap<char, HashSet<char>> {
+        let first_sets = self.get_first_sets();
+        let mut sets: HashMap<_, _> = self
+            .non_terminals
+            .iter()
+            .map(|symbol| (*symbol, HashSet::new()))
+            .collect();
+        sets.entry(self.start).or_default().insert(END_TERMINAL);
+        // Non-terminals whose FOLLOW set is included in the ones of the others.
+        let mut edges = HashMap::<char, HashSet<char>>::new();
+
+        for (rule_left, rule_right) in self.rules.flat_iter() {
+            let symbols: Vec<_> = rule_right.chars().collect();
+
+            for (i, symbol) in symbols.iter().enumerate() {
+                if !self.non_terminals.contains(symbol) {
+                    continue;
+                }
+
+                let rest: String = symbols[(i + 1)..].iter().collect();
+                let mut found = Self::first_with(&first_sets, &rest);
+
+                if found.remove(&EPS_TERMINAL) {
+                    edges.entry(*rule_left).or_default().insert(*symbol);
+                }
+
+                sets.entry(*symbol).or_default().extend(found);
+            }
+        }
+
+        let mut queue: VecDeque<_> = sets.keys().copied().collect();
+
+        while let Some(symbol) = queue.pop_front() {
+            let found = sets[&symbol].clone();
+
+            for next in edges.get(&symbol).into_iter().flatten() {
+                let entry = sets.entry(*next).or_default();
+                let prev_size = entry.len();
+                entry.extend(found.iter().copied());
+
+                if entry.len() != prev_size {
+                    queue.push_back(*next);
+                }
+            }
+        }
+
+        sets
+    }
+
+    pub(crate) fn get_first_sets(&self) -> HashMap<char, HashSet<char>> {
+        let nullable = self.nullable();
+        let mut sets: HashMap<_, _> = self
+            .non_terminals
+            .iter()
+            .map(|symbol| {
+                let eps = nullable.contains(symbol).then_some(EPS_TERMINAL);
+                (*symbol, HashSet::from_iter(eps))
+            })
+            .collect();
+
+        let mut users = HashMap::<char, Vec<_>>::new();
+
+        for rule in self.rules.flat_iter() {
+            for symbol in rule.1.chars() {
+                users.entry(symbol).or_default().push(rule);
+            }
+        }
+
+        // Only the rules using a symbol whose set grew are looked at again.
+        let mut queue: VecDeque<_> = self.rules.flat_iter().collect();
+
+        while let Some((rule_left, rule_right)) = queue.pop_front() {
+            let found = Self::first_with(&sets, rule_right);
+            let entry = sets.entry(*rule_left).or_default();
+            let prev_size = entry.len();
+            entry.extend(found);
+
+            if entry.len() != prev_size {
+                queue.extend(users.get(rule_left).into_iter().flatten());
+            }
+        }
+
+        sets
+    }
+
+    pub(crate) fn first_with(sets: &HashMap<char, HashSet<char>>, s: &str) -> HashSet<char> {
+        let mut found = HashSet::new();
+
+        for symbol in s.chars() {
+            if symbol == EPS_TERMINAL {
+                continue;
+            }
+
+            match sets.get(&symbol) {
+                Some(first) => {
+                    found.extend(first.iter().filter(|letter| **letter != EPS_TERMINAL));
+
+                    if !first.contains(&EPS_TERMINAL) {
+                        return found;
+                    }
+                }
+                None => {
+                    found.insert(symbol);
+                    return found;
+                }
+            }
+        }
+
+        found.insert(EPS_TERMINAL);
+        found
+    }
+}
+
+/// Strongly connected components of the graph that contain a cycle.
+fn get_cycles(graph: &HashMap<char, HashSet<char>>) -> Vec<Vec<char>> {
+    let mut tarjan = Tarjan {
+        graph,
+        counter: 0,
+        index: HashMap::new(),
+        low: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    let mut vertices: Vec<_> = graph.keys().copied().collect();
+    vertices.sort();
+
+    for vertex in vertices {
+        if !tarjan.index.contains_key(&vertex) {
+            tarjan.visit(vertex);
+        }
+    }
+
+    let mut cycles: Vec<_> = tarjan
+        .components
+        .into_iter()
+        .filter(|component| {
+            component.len() > 1
+                || graph
+                    .get(&component[0])
+                    .is_some_and(|next| next.contains(&component[0]))
+        })
+        .map(|mut component| {
+            component.sort();
+            component
+        })
+        .collect();
+    cycles.sort();
+    cycles
+}
+
+struct Tarjan<'a> {
+    graph: &'a HashMap<char, HashSet<char>>,
+    counter: usize,
+    index: HashMap<char, usize>,
+    low: HashMap<char, usize>,
+    stack: Vec<char>,
+    on_stack: HashSet<char>,
+    components: Vec<Vec<char>>,
+}
+
+impl Tarjan<'_> {
+    fn visit(&mut self, vertex: char) {
+        self.index.insert(vertex, self.counter);
+        self.low.insert(vertex, self.counter);
+        self.counter += 1;
+        self.stack.push(vertex);
+        self.on_stack.insert(vertex);
+
+        for next in self.graph.get(&vertex).into_iter().flatten() {
+            if !self.index.contains_key(next) {
+                self.visit(*next);
+                let low = self.low[&vertex].min(self.low[next]);
+                self.low.insert(vertex, low);
+            } else if self.on_stack.contains(next) {
+                let low = self.low[&vertex].min(self.index[next]);
+                self.low.insert(vertex, low);
+            }
+        }
+
+        if self.low[&vertex] == self.index[&vertex] {
+            let mut component = Vec::new();
+
+            while let Some(top) = self.stack.pop() {
+                self.on_stack.remove(&top);
+                component.push(top);
+
+                if top == vertex {
+                    break;
+                }
+            }
+
+            self.components.push(component);
+        }
+    }
+}
+
+/// Sorted symbols in braces, without `EPS_TERMINAL` and with `END_TERMINAL` as `$`.
+fn get_symbols_report(symbols: &HashSet<char>) -> String {
+    let mut symbols: Vec<_> = symbols
+        .iter()
+        .copied()
+        .filter(|symbol| *symbol != EPS_TERMINAL)
+        .collect();
+    symbols.sort();
+    let symbols: Vec<_> = symbols
+        .into_iter()
+        .map(|symbol| match symbol {
+            END_TERMINAL => "$".to_string(),
+            _ => symbol.to_string(),
+        })
+        .collect();
+    format!("{{{}}}", symbols.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_unit_test_1() {
+        let grammar = get_test_grammar();
+        assert_eq!(grammar.first('N'), HashSet::from(['a', '(']));
+        assert_eq!(grammar.first('+'), HashSet::from(['+']));
+        assert_eq!(grammar.first_of_string("+N"), HashSet::from(['+']));
+        assert_eq!(grammar.first_of_string(""), HashSet::from([EPS_TERMINAL]));
+    }
+
+    #[test]
+    fn first_unit_test_2() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->a\nA->\nB->b\nB->\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.first('A'), HashSet::from(['a', EPS_TERMINAL]));
+        assert_eq!(grammar.first('S'), HashSet::from(['a', 'b', EPS_TERMINAL]));
+        assert_eq!(grammar.first_of_string("Ab"), HashSet::from(['a', 'b']));
+        assert_eq!(grammar.first_of_string("AAa"), HashSet::from(['a']));
+        assert!(!grammar.is_terminal(&EPS_TERMINAL));
+
+        for symbol in ['S', 'A', 'B'] {
+            assert!(!grammar.follow(symbol).contains(&EPS_TERMINAL));
+        }
+    }
+
+    #[test]
+    fn initial_terminals_unit_test_1() {
+        assert_eq!(
+            get_test_grammar().initial_terminals(),
+            HashSet::from(['a', '('])
+        );
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->a\nA->\nB->b\nB->\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.initial_terminals(), HashSet::from(['a', 'b']));
+        let grammar = CFGrammar::from_str("S\na\nS->\nS").expect("Failed to parse the grammar.");
+        assert!(grammar.initial_terminals().is_empty());
+    }
+
+    #[test]
+    fn first_unit_test_3() {
+        // A chain A1 -> A2 -> ... -> A100 -> a of private use symbols.
+        let symbols: Vec<_> = (0xE000..0xE064).filter_map(char::from_u32).collect();
+        let mut rules = MultiMap::new();
+
+        for pair in symbols.windows(2) {
+            rules.insert(pair[0], pair[1].to_string());
+        }
+
+        rules.insert(symbols[99], "a".to_string());
+        let non_terminals = symbols.iter().copied().collect();
+        let grammar = CFGrammar::new(&HashSet::from(['a']), &non_terminals, &rules, symbols[0]);
+
+        for symbol in symbols.iter() {
+            assert_eq!(grammar.first(*symbol), HashSet::from(['a']));
+            assert_eq!(grammar.follow(*symbol), HashSet::from([END_TERMINAL]));
+        }
+    }
+
+    #[test]
+    fn follow_unit_test_1() {
+        let grammar = get_test_grammar();
+        assert_eq!(grammar.follow('N'), HashSet::from([')', END_TERMINAL]));
+        assert_eq!(grammar.follow('T'), HashSet::from(['+', ')', END_TERMINAL]));
+        assert_eq!(
+            grammar.follow('F'),
+            HashSet::from(['*', '+', ')', END_TERMINAL])
+        );
+    }
+
+    #[test]
+    fn follow_unit_test_2() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->a\nA->\nB->b\nB->\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.follow('A'), HashSet::from(['b', END_TERMINAL]));
+        assert_eq!(grammar.follow('B'), HashSet::from([END_TERMINAL]));
+    }
+
+    #[test]
+    fn unreachable_unit_test_1() {
+        let grammar = get_test_grammar();
+        assert!(grammar.unreachable_non_terminals().is_empty());
+    }
+
+    #[test]
+    fn unreachable_unit_test_2() {
+        let grammar = CFGrammar::from_str("SABCD\nab\nS->aA\nA->b\nB->C\nC->a\nD->D\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(
+            grammar.unreachable_non_terminals(),
+            HashSet::from(['B', 'C', 'D'])
+        );
+    }
+
+    #[test]
+    fn unproductive_unit_test_1() {
+        let grammar = get_test_grammar();
+        assert!(grammar.unproductive_non_terminals().is_empty());
+    }
+
+    #[test]
+    fn unproductive_unit_test_2() {
+        let grammar = CFGrammar::from_str("SABC\nab\nS->a\nS->Cb\nA->A\nB->Ba\nC->\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(
+            grammar.unproductive_non_terminals(),
+            HashSet::from(['A', 'B'])
+        );
+    }
+
+    #[test]
+    fn unproductive_unit_test_3() {
+        let grammar =
+            CFGrammar::from_str("SA\na\nS->aA\nA->Sa\nS").expect("Failed to parse the grammar.");
+        assert_eq!(
+            grammar.unproductive_non_terminals(),
+            HashSet::from(['S', 'A'])
+        );
+    }
+
+    #[test]
+    fn left_recursion_unit_test_1() {
+        let grammar = get_test_grammar();
+        assert!(!grammar.is_left_recursive());
+    }
+
+    #[test]
+    fn left_recursion_unit_test_2() {
+        let grammar = CFGrammar::from_str("ET\n+a\nE->E+T\nE->T\nT->a\nE")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.is_left_recursive());
+        assert_eq!(grammar.left_recursive_cycles(), vec![vec!['E']]);
+    }
+
+    #[test]
+    fn left_recursion_unit_test_3() {
+        let grammar = CFGrammar::from_str("SABC\nabc\nS->Aa\nA->CBb\nA->c\nB->Sa\nC->\nS")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.is_left_recursive());
+        assert_eq!(grammar.left_recursive_cycles(), vec![vec!['A', 'B', 'S']]);
+    }
+
+    #[test]
+    fn accepts_empty_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb|\nS").expect("Failed to parse the grammar.");
+        assert!(grammar.accepts_empty());
+        assert!(!get_test_grammar().accepts_empty());
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->a|\nB->AA|b\nS")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.accepts_empty());
+        let grammar =
+            CFGrammar::from_str("SA\na\nS->Aa\nA->\nS").expect("Failed to parse the grammar.");
+        assert!(!grammar.accepts_empty());
+    }
+
+    #[test]
+    fn nullable_unit_test_1() {
+        assert!(get_test_grammar().nullable().is_empty());
+        let grammar = CFGrammar::from_str("SABC\nab\nS->AB\nA->a|\nB->AA|b\nC->Cb|a\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.nullable(), HashSet::from(['S', 'A', 'B']));
+    }
+
+    #[test]
+    fn analysis_report_unit_test_1() {
+        let grammar = CFGrammar::from_str("SABC\nab\nS->AB\nA->a|\nB->b\nC->Cb\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(
+            grammar.analysis_report(),
+            "A: FIRST = {a}, FOLLOW = {b}, nullable = true\n\
+             B: FIRST = {b}, FOLLOW = {$}, nullable = false\n\
+             C: FIRST = {}, FOLLOW = {b}, nullable = false\n\
+             S: FIRST = {a, b}, FOLLOW = {$}, nullable = false\n\
+             Unreachable: {C}\n\
+             Unproductive: {C}\n"
+        );
+    }
+
+    #[test]
+    fn cycle_unit_test_1() {
+        assert!(!get_test_grammar().has_cycle());
+        let grammar = CFGrammar::from_str("SAB\na\nS->A\nA->B\nA->a\nB->A\nS")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.has_cycle());
+        let grammar = CFGrammar::from_str("SA\na\nS->AS\nS->\nA->a\nA->\nS")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.has_cycle());
+        let grammar = CFGrammar::from_str("SA\na\nS->AS\nS->\nA->a\nS")
+            .expect("Failed to parse the grammar.");
+        assert!(!grammar.has_cycle());
+        let grammar =
+            CFGrammar::from_str("E\n+a\nE->E+a\nE->a\nE").expect("Failed to parse the grammar.");
+        assert!(grammar.is_left_recursive());
+        assert!(!grammar.has_cycle());
+    }
+}
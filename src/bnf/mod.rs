@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use super::tokens::{get_token_items, Notation, TokenItem};
+use super::transform::{get_fresh_symbols, get_grammar};
+use super::*;
+
+impl CFGrammar {
+    /// Parse rules like `<expr> ::= <expr> "+" <term> | <term>`. Every named
+    /// non-terminal is interned as a unique private symbol, the returned vocabulary
+    /// gives back the original names. The left part of the first rule is the start.
+    pub fn from_bnf(s: &str) -> Result<(CFGrammar, Vocabulary), anyhow::Error> {
+        let tokens = get_token_items(s, Notation::Bnf)?;
+        let mut used: HashSet<char> = s.chars().collect();
+        let mut names: Vec<_> = tokens
+            .iter()
+            .filter_map(|token| match token {
+                TokenItem::Name(name) => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        names.sort();
+        names.dedup();
+        let symbols: HashMap<String, char> = names
+            .iter()
+            .cloned()
+            .zip(get_fresh_symbols(&mut used, names.len())?)
+            .collect();
+        let mut terminals = HashSet::new();
+        let mut defined = HashSet::new();
+        let mut rules = Vec::new();
+        let mut pos = 0;
+
+        while pos < tokens.len() {
+            let (TokenItem::Name(name), Some(TokenItem::Define)) =
+                (&tokens[pos], tokens.get(pos + 1))
+            else {
+                bail!("Every rule must start with '<name> ::='.");
+            };
+
+            let rule_left = symbols[name];
+            defined.insert(rule_left);
+            pos += 2;
+            let mut rule_right = String::new();
+
+            while pos < tokens.len() {
+                if let Some(TokenItem::Define) = tokens.get(pos + 1) {
+                    break;
+                }
+
+                match &tokens[pos] {
+                    TokenItem::Name(name) => rule_right.push(symbols[name]),
+                    TokenItem::Terminal(letters) => {
+                        terminals.extend(letters.chars());
+                        rule_right.push_str(letters);
+                    }
+                    TokenItem::Alternative => {
+                        rules.push((rule_left, std::mem::take(&mut rule_right)));
+                    }
+                    TokenItem::Define => bail!("Unexpected '::='."),
+                }
+
+                pos += 1;
+            }
+
+            rules.push((rule_left, rule_right));
+        }
+
+        let names: HashMap<char, String> = symbols
+            .into_iter()
+            .map(|(name, symbol)| (symbol, name))
+            .collect();
+
+        if let Some(symbol) = names.keys().find(|symbol| !defined.contains(symbol)) {
+            bail!("Non-terminal <{}> has no rules.", names[symbol]);
+        }
+
+        let Some(start) = rules.first().map(|rule| rule.0) else {
+            bail!("There must be at least one rule.");
+        };
+
+        let non_terminals = names.keys().copied().collect();
+        let grammar = get_grammar(&terminals, &non_terminals, rules, start);
+        Ok((grammar, Vocabulary::from_names(names, &terminals)))
+    }
+}
+
+#[cfg(all(test, feature = "earley"))]
+mod tests {
+    use super::*;
+    use crate::earley::EarleyParser;
+
+    #[test]
+    fn bnf_unit_test_1() {
+        let (grammar, vocabulary) = CFGrammar::from_bnf(
+            "<expr> ::= <expr> \"+\" <term> | <term>
+             <term> ::= <term> \"*\" <factor>
+                      | <factor>
+             <factor> ::= \"(\" <expr> \")\" | 'x'",
+        )
+        .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "x+x*(x+x)"));
+        assert!(!Parser::predict(&mut parser, "x+"));
+        let tree = parser.parse("x").expect("Parse unsuccessful");
+        assert_eq!(vocabulary.name(tree.symbol()), Some("expr"));
+        assert!(vocabulary.predict_tokens(&mut parser, &["x", "*", "(", "x", ")"]));
+        assert!(vocabulary.letters(&["expr"]).is_err());
+    }
+
+    #[test]
+    fn bnf_should_fail_1() {
+        assert!(CFGrammar::from_bnf("").is_err());
+        assert!(CFGrammar::from_bnf("<s> ::= <a>").is_err());
+        assert!(CFGrammar::from_bnf("<s> = 'a'").is_err());
+        assert!(CFGrammar::from_bnf("<s> ::= 'a").is_err());
+        assert!(CFGrammar::from_bnf("'a' ::= <s>").is_err());
+        assert!(CFGrammar::from_bnf("<s ::= 'a'").is_err());
+        assert!(CFGrammar::from_bnf("<s> ::= 'a' <t").is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use super::*;
+
+/// Step by step construction of a `CFGrammar`.
+#[derive(Debug, Default, Clone)]
+pub struct CFGrammarBuilder {
+    /// Declared terminal symbols.
+    terminals: HashSet<char>,
+    /// Declared non-terminal symbols.
+    non_terminals: HashSet<char>,
+    /// Rules in the order of addition.
+    rules: Vec<CFRule>,
+    /// Start non-terminal.
+    start: Option<char>,
+}
+
+impl CFGrammarBuilder {
+    pub fn new() -> Self {
+        Self {
+            terminals: HashSet::new(),
+            non_terminals: HashSet::new(),
+            rules: Vec::new(),
+            start: None,
+        }
+    }
+
+    pub fn terminal(mut self, symbol: char) -> Self {
+        self.terminals.insert(symbol);
+        self
+    }
+
+    pub fn non_terminal(mut self, symbol: char) -> Self {
+        self.non_terminals.insert(symbol);
+        self
+    }
+
+    pub fn rule(mut self, rule_left: char, rule_right: &str) -> Self {
+        self.rules.push((rule_left, rule_right.to_string()));
+        self
+    }
+
+    pub fn start(mut self, symbol: char) -> Self {
+        self.start = Some(symbol);
+        self
+    }
+
+    /// Build the grammar, registering the undeclared symbols used in the rules.
+    ///
+    /// An undeclared symbol is a non-terminal if it has rules and a terminal otherwise.
+    pub fn build(self) -> Result<CFGrammar, anyhow::Error> {
+        let Some(start) = self.start else {
+            bail!("There must be exactly one start rule.");
+        };
+
+        let mut terminals = self.terminals;
+        let mut non_terminals = self.non_terminals;
+        let mut rules = MultiMap::new();
+
+        if let Some(symbol) = terminals.intersection(&non_terminals).next() {
+            bail!("The symbol {symbol} can not be both a terminal and a non-terminal.");
+        }
+
+        for (rule_left, _) in self.rules.iter() {
+            if terminals.contains(rule_left) {
+                bail!(
+                    "Only non-terminals can be present in the left part of the CF grammar {rule_left}."
+                );
+            }
+
+            non_terminals.insert(*rule_left);
+        }
+
+        for (rule_left, rule_right) in self.rules {
+            for symbol in rule_right.chars() {
+                if !non_terminals.contains(&symbol) {
+                    terminals.insert(symbol);
+                }
+            }
+
+            rules.insert(rule_left, rule_right);
+        }
+
+        if terminals.contains(&start) {
+            bail!("The start symbol {start} must be a non-terminal.");
+        }
+
+        non_terminals.insert(start);
+        Ok(CFGrammar::new(&terminals, &non_terminals, &rules, start))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_unit_test_1() {
+        let test_grammar = get_test_grammar();
+        let built_grammar = CFGrammarBuilder::new()
+            .rule('S', "N")
+            .rule('N', "T+N")
+            .rule('N', "T")
+            .rule('T', "F*T")
+            .rule('T', "F")
+            .rule('F', "(N)")
+            .rule('F', "a")
+            .start('S')
+            .build()
+            .unwrap();
+        assert_eq!(test_grammar.non_terminals, built_grammar.non_terminals);
+        assert_eq!(test_grammar.terminals, built_grammar.terminals);
+        assert_eq!(test_grammar.rules, built_grammar.rules);
+        assert_eq!(test_grammar.start, built_grammar.start);
+    }
+
+    #[test]
+    fn builder_unit_test_2() {
+        let grammar = CFGrammarBuilder::new()
+            .terminal('b')
+            .non_terminal('A')
+            .rule('S', "aA")
+            .start('S')
+            .build()
+            .unwrap();
+        assert!(grammar.is_non_terminal(&'A'));
+        assert!(grammar.is_terminal(&'a'));
+        assert!(grammar.is_terminal(&'b'));
+    }
+
+    #[test]
+    fn builder_should_fail_1() {
+        let grammar = CFGrammarBuilder::new()
+            .terminal('a')
+            .rule('a', "S")
+            .start('S')
+            .build();
+        assert!(grammar.is_err());
+    }
+
+    #[test]
+    fn builder_should_fail_2() {
+        let grammar = CFGrammarBuilder::new().rule('S', "a").build();
+        assert!(grammar.is_err());
+    }
+
+    #[test]
+    fn builder_should_fail_3() {
+        let grammar = CFGrammarBuilder::new()
+            .terminal('S')
+            .non_terminal('S')
+            .rule('S', "a")
+            .start('S')
+            .build();
+        assert!(grammar.is_err());
+    }
+}
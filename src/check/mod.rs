@@ -0,0 +1,146 @@
+use std::fmt;
+
+use super::earley::EarleyParser;
+use super::lr1::LR1Parser;
+use super::*;
+
+/// Word on which the Earley and the LR(1) parsers disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The grammar in the format of `from_str`.
+    pub grammar: String,
+    pub word: String,
+    /// Verdicts of the Earley and the LR(1) parser.
+    pub accepted: (bool, bool),
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Earley {} and LR(1) {} the word {:?} of the grammar:\n{}",
+            get_verdict(self.accepted.0),
+            get_verdict(self.accepted.1),
+            self.word,
+            self.grammar
+        )
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// Run the Earley and the LR(1) parsers on every word and return the first one
+/// they disagree on. Words with letters that are not terminals are skipped, and
+/// so are the grammars either parser can not be fitted to.
+pub fn cross_check(grammar: &CFGrammar, words: &[&str]) -> Result<(), Mismatch> {
+    let mut earley = EarleyParser::new();
+    let mut lr1 = LR1Parser::new();
+
+    if earley.fit(grammar).is_err() || lr1.fit(grammar).is_err() {
+        return Ok(());
+    }
+
+    for word in words.iter().filter(|word| grammar.check_word(word).is_ok()) {
+        let accepted = (earley.predict(word), lr1.predict(word));
+
+        if accepted.0 != accepted.1 {
+            return Err(Mismatch {
+                grammar: grammar.to_string(),
+                word: word.to_string(),
+                accepted,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn get_verdict(accepted: bool) -> &'static str {
+    if accepted {
+        "accepts"
+    } else {
+        "rejects"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cross_check_unit_test_1() {
+        let grammars = [
+            "SNTF\na+*()\nS->N\nN->T+N|T\nT->F*T|F\nF->(N)|a\nS",
+            "SA\nab\nS->aSb|A\nA->bA|\nS",
+            "S\nα😀\nS->αS😀|\nS",
+            "SE\na+\nS->E\nE->E+E|a\nS",
+        ];
+        let words = [
+            "", "a", "a+a", "(a)*a", "ab", "abbb", "aabb", "α😀", "αα😀", "xy",
+        ];
+
+        for grammar in grammars {
+            let grammar = CFGrammar::from_str(grammar).expect("Failed to parse the grammar.");
+            assert_eq!(cross_check(&grammar, &words), Ok(()));
+
+            for word in grammar.words_up_to(6) {
+                assert_eq!(cross_check(&grammar, &[&word]), Ok(()), "{word}");
+            }
+        }
+    }
+
+    #[test]
+    fn cross_check_random_test_1() {
+        let mut rng = XorShiftRng::new(350);
+        // Every word of at most five letters.
+        let mut words = vec![String::new()];
+
+        for i in 0.. {
+            if words[i].len() == 5 {
+                break;
+            }
+
+            words.push(words[i].clone() + "a");
+            words.push(words[i].clone() + "b");
+        }
+
+        let words: Vec<_> = words.iter().map(String::as_str).collect();
+
+        for _ in 0..200 {
+            let mut builder = CFGrammarBuilder::new().terminal('a').terminal('b');
+
+            for symbol in ['S', 'A', 'B'] {
+                builder = builder.non_terminal(symbol);
+
+                for _ in 0..=(rng.next_u64() % 3) {
+                    let len = rng.next_u64() % 4;
+                    let rule_right: String = (0..len)
+                        .map(|_| ['a', 'b', 'S', 'A', 'B'][(rng.next_u64() % 5) as usize])
+                        .collect();
+                    builder = builder.rule(symbol, &rule_right);
+                }
+            }
+
+            let grammar = builder
+                .start('S')
+                .build()
+                .expect("Failed to build the grammar.");
+            assert_eq!(cross_check(&grammar, &words), Ok(()));
+        }
+    }
+
+    #[test]
+    fn cross_check_unit_test_2() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aS|b\nS").expect("Failed to parse the grammar.");
+        let mismatch = Mismatch {
+            grammar: grammar.to_string(),
+            word: "ab".to_string(),
+            accepted: (true, false),
+        };
+        assert_eq!(
+            mismatch.to_string(),
+            format!("Earley accepts and LR(1) rejects the word \"ab\" of the grammar:\n{grammar}")
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use super::*;
+
+#[derive(Debug, Default, Clone)]
+pub struct CYKParser {
+    /// Start non-terminal of the fitted grammar.
+    start: Option<char>,
+    /// Whether the start non-terminal derives the empty word.
+    accepts_empty: bool,
+    /// Non-terminals deriving each terminal.
+    terminal_rules: HashMap<char, HashSet<char>>,
+    /// Rules of the form `A -> BC`.
+    binary_rules: Vec<(char, char, char)>,
+}
+
+impl Parser for CYKParser {
+    fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        self.reset();
+        self.check_grammar(grammar)
+            .with_context(|| "The grammar is not in the Chomsky normal form.")?;
+        self.start = Some(grammar.get_user_start());
+
+        for (rule_left, rule_right) in grammar.get_user_rules() {
+            let symbols: Vec<_> = rule_right.chars().collect();
+
+            match symbols.len() {
+                0 => self.accepts_empty = true,
+                1 => {
+                    self.terminal_rules
+                        .entry(symbols[0])
+                        .or_default()
+                        .insert(rule_left);
+                }
+                _ => self.binary_rules.push((rule_left, symbols[0], symbols[1])),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build a parse tree of the word, one of many for ambiguous grammars.
+    ///
+    /// The table does not track prefixes, so a rejected word consisting of known
+    /// letters is reported at its first letter.
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError> {
+        let Some(start) = self.start else {
+            return Err(ParseError::NotFitted);
+        };
+
+        let letters: Vec<_> = word.chars().collect();
+
+        if letters.is_empty() {
+            return if self.accepts_empty {
+                Ok(ParseTree::Node {
+                    rule: (start, String::new()),
+                    children: Vec::new(),
+                })
+            } else {
+                Err(ParseError::UnexpectedEnd)
+            };
+        }
+
+        // table[i][len - 1] maps the non-terminals deriving letters[i..(i + len)]
+        // to the split point and the right part of the rule used.
+        let mut table = vec![vec![HashMap::<char, CYKSplit>::new(); letters.len()]; letters.len()];
+
+        for (pos, letter) in letters.iter().enumerate() {
+            let Some(symbols) = self.terminal_rules.get(letter) else {
+                return Err(ParseError::UnexpectedSymbol {
+                    pos,
+                    found: *letter,
+                });
+            };
+
+            for symbol in symbols.iter() {
+                table[pos][0].insert(*symbol, None);
+            }
+        }
+
+        for len in 2..=letters.len() {
+            for i in 0..=(letters.len() - len) {
+                for split in 1..len {
+                    for (rule_left, first, second) in self.binary_rules.iter() {
+                        if table[i][split - 1].contains_key(first)
+                            && table[i + split][len - split - 1].contains_key(second)
+                        {
+                            table[i][len - 1]
+                                .entry(*rule_left)
+                                .or_insert(Some((split, *first, *second)));
+                        }
+                    }
+                }
+            }
+        }
+
+        if !table[0][letters.len() - 1].contains_key(&start) {
+            return Err(ParseError::UnexpectedSymbol {
+                pos: 0,
+                found: letters[0],
+            });
+        }
+
+        Ok(Self::build_tree(&table, &letters, start, 0, letters.len()))
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+type CYKSplit = Option<(usize, char, char)>;
+
+impl CYKParser {
+    pub fn new() -> Self {
+        Self {
+            start: None,
+            accepts_empty: false,
+            terminal_rules: HashMap::new(),
+            binary_rules: Vec::new(),
+        }
+    }
+
+    fn build_tree(
+        table: &[Vec<HashMap<char, CYKSplit>>],
+        letters: &[char],
+        symbol: char,
+        begin: usize,
+        len: usize,
+    ) -> ParseTree {
+        match table[begin][len - 1][&symbol] {
+            Some((split, first, second)) => ParseTree::Node {
+                rule: (symbol, String::from_iter([first, second])),
+                children: vec![
+                    Self::build_tree(table, letters, first, begin, split),
+                    Self::build_tree(table, letters, second, begin + split, len - split),
+                ],
+            },
+            None => ParseTree::Node {
+                rule: (symbol, letters[begin].to_string()),
+                children: vec![ParseTree::Leaf(letters[begin])],
+            },
+        }
+    }
+
+    fn check_grammar(&self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        let start = grammar.get_user_start();
+        let rules = grammar.get_user_rules();
+        let has_empty = rules.iter().any(|rule| rule.1.is_empty());
+
+        for (rule_left, rule_right) in rules.iter() {
+            let symbols: Vec<_> = rule_right.chars().collect();
+
+            match symbols.len() {
+                0 if *rule_left == start => {}
+                0 => {
+                    bail!("Only the start non-terminal may have an empty rule, found {rule_left}.")
+                }
+                1 if grammar.is_terminal(&symbols[0]) => {}
+                2 if symbols.iter().all(|symbol| grammar.is_non_terminal(symbol)) => {
+                    if has_empty && symbols.contains(&start) {
+                        bail!("The start non-terminal with an empty rule is used in {rule_left} -> {rule_right}.");
+                    }
+                }
+                _ => bail!("The rule {rule_left} -> {rule_right} is neither A -> BC nor A -> a."),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cyk_unit_test_1() {
+        let grammar = get_test_grammar().to_cnf();
+        let mut parser = CYKParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "(a+a)"));
+        assert!(!Parser::predict(&mut parser, "(a+a*a())"));
+        assert!(!Parser::predict(&mut parser, ""));
+    }
+
+    #[test]
+    fn cyk_unit_test_2() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        let mut parser = CYKParser::new();
+        parser.fit(&grammar.to_cnf()).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, ""));
+        assert!(Parser::predict(&mut parser, "aabb"));
+        assert!(!Parser::predict(&mut parser, "abb"));
+    }
+
+    #[cfg(feature = "earley")]
+    #[test]
+    fn cyk_unit_test_3() {
+        let grammar = get_test_grammar();
+        let mut parser = CYKParser::new();
+        let mut earley = crate::earley::EarleyParser::new();
+        parser.fit(&grammar.to_cnf()).expect("Fit unsuccessful");
+        earley.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in [
+            "a",
+            "a+a",
+            "(a*a)+a",
+            "((a))",
+            "a+",
+            "()",
+            "a*(a+a)*a",
+            "a)",
+        ] {
+            assert_eq!(
+                Parser::predict(&mut parser, word),
+                Parser::predict(&mut earley, word),
+                "{word}"
+            );
+        }
+    }
+
+    #[test]
+    fn cyk_parse_test_1() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->a\nB->b\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = CYKParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let tree = ParseTree::Node {
+            rule: ('S', "AB".to_string()),
+            children: vec![
+                ParseTree::Node {
+                    rule: ('A', "a".to_string()),
+                    children: vec![ParseTree::Leaf('a')],
+                },
+                ParseTree::Node {
+                    rule: ('B', "b".to_string()),
+                    children: vec![ParseTree::Leaf('b')],
+                },
+            ],
+        };
+        assert_eq!(parser.parse("ab"), Ok(tree));
+        assert_eq!(
+            parser.parse("ac"),
+            Err(ParseError::UnexpectedSymbol { pos: 1, found: 'c' })
+        );
+        assert_eq!(parser.parse(""), Err(ParseError::UnexpectedEnd));
+        assert_eq!(CYKParser::new().parse("ab"), Err(ParseError::NotFitted));
+    }
+
+    #[test]
+    fn cyk_should_fail_1() {
+        let grammar = get_test_grammar();
+        let mut parser = CYKParser::new();
+        assert!(parser.fit(&grammar).is_err());
+
+        parser.fit(&grammar.to_cnf()).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a+a"));
+        assert!(parser.fit(&grammar).is_err());
+        assert!(!Parser::predict(&mut parser, "a+a"));
+        assert_eq!(parser.parse("a+a"), Err(ParseError::NotFitted));
+    }
+}
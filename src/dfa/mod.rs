@@ -0,0 +1,242 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::transform::{get_fresh_symbols, get_grammar, get_useful_rules};
+use super::*;
+
+/// Deterministic finite automaton over letters, rejecting on missing transitions.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SimpleDfa {
+    start: usize,
+    transitions: HashMap<(usize, char), usize>,
+    accepting: HashSet<usize>,
+}
+
+/// Non-terminal deriving the words leading the automaton from one state to another.
+type Triple = (usize, char, usize);
+
+impl SimpleDfa {
+    pub fn new(
+        start: usize,
+        transitions: &HashMap<(usize, char), usize>,
+        accepting: &HashSet<usize>,
+    ) -> Self {
+        Self {
+            start,
+            transitions: transitions.clone(),
+            accepting: accepting.clone(),
+        }
+    }
+
+    /// Check if the automaton ends in an accepting state after reading the word.
+    pub fn accepts(&self, word: &str) -> bool {
+        let mut state = self.start;
+
+        for letter in word.chars() {
+            match self.transitions.get(&(state, letter)) {
+                Some(next) => state = *next,
+                None => return false,
+            }
+        }
+
+        self.accepting.contains(&state)
+    }
+}
+
+impl CFGrammar {
+    /// Grammar of the words of the language accepted by the automaton.
+    ///
+    /// The grammar is brought to the Chomsky normal form, and every non-terminal
+    /// `A` is split into fresh ones for the pairs of states the words derived from
+    /// `A` lead the automaton between. Only the productive pairs get a symbol, the
+    /// construction fails if there are more of them than unused symbols.
+    pub fn intersect_regular(&self, dfa: &SimpleDfa) -> Result<CFGrammar, anyhow::Error> {
+        let cnf = self.to_cnf();
+        let cnf_start = cnf.get_user_start();
+        let mut binary_rules = Vec::new();
+        let mut triple_rules = BTreeSet::<(Triple, Vec<Triple>)>::new();
+        let mut terminal_rules = BTreeSet::<(Triple, char)>::new();
+        let mut productive = BTreeSet::new();
+
+        for (rule_left, rule_right) in cnf.get_user_rules() {
+            let symbols: Vec<_> = rule_right.chars().collect();
+
+            match symbols[..] {
+                [letter] => {
+                    for ((from, _), to) in dfa
+                        .transitions
+                        .iter()
+                        .filter(|((_, other), _)| *other == letter)
+                    {
+                        let triple = (*from, rule_left, *to);
+                        terminal_rules.insert((triple, letter));
+                        productive.insert(triple);
+                    }
+                }
+                [first, second] => binary_rules.push((rule_left, first, second)),
+                _ => {}
+            }
+        }
+
+        // Combine the productive triples until no new ones appear.
+        loop {
+            let mut by_symbol = BTreeMap::<char, Vec<Triple>>::new();
+            let mut by_start = BTreeMap::<(char, usize), Vec<Triple>>::new();
+
+            for triple in productive.iter() {
+                by_symbol.entry(triple.1).or_default().push(*triple);
+                by_start
+                    .entry((triple.1, triple.0))
+                    .or_default()
+                    .push(*triple);
+            }
+
+            let mut new_triples = Vec::new();
+
+            for (rule_left, first, second) in binary_rules.iter() {
+                for left in by_symbol.get(first).into_iter().flatten() {
+                    for right in by_start.get(&(*second, left.2)).into_iter().flatten() {
+                        let triple = (left.0, *rule_left, right.2);
+                        triple_rules.insert((triple, vec![*left, *right]));
+                        new_triples.push(triple);
+                    }
+                }
+            }
+
+            let size = productive.len();
+            productive.extend(new_triples);
+
+            if productive.len() == size {
+                break;
+            }
+        }
+
+        let mut used = self.get_used_symbols();
+        let fresh = get_fresh_symbols(&mut used, productive.len() + 1)?;
+        let start = fresh[0];
+        let symbols: HashMap<_, _> = productive
+            .iter()
+            .copied()
+            .zip(fresh[1..].iter().copied())
+            .collect();
+        let mut rules = BTreeSet::new();
+
+        for (triple, rule_right) in triple_rules {
+            rules.insert((
+                symbols[&triple],
+                rule_right.iter().map(|other| symbols[other]).collect(),
+            ));
+        }
+
+        for (triple, letter) in terminal_rules {
+            rules.insert((symbols[&triple], letter.to_string()));
+        }
+
+        for accepting in dfa.accepting.iter() {
+            if let Some(symbol) = symbols.get(&(dfa.start, cnf_start, *accepting)) {
+                rules.insert((start, symbol.to_string()));
+            }
+        }
+
+        if cnf.accepts_empty() && dfa.accepting.contains(&dfa.start) {
+            rules.insert((start, String::new()));
+        }
+
+        let non_terminals = symbols.values().copied().chain([start]).collect();
+        let rules = get_useful_rules(&rules, &non_terminals, start);
+        let non_terminals = rules.iter().map(|rule| rule.0).chain([start]).collect();
+        Ok(get_grammar(
+            &self.get_user_terminals(),
+            &non_terminals,
+            rules,
+            start,
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "earley"))]
+mod tests {
+    use super::*;
+    use crate::earley::EarleyParser;
+
+    #[test]
+    fn dfa_unit_test_1() {
+        // Words with an even number of letters.
+        let dfa = SimpleDfa::new(
+            0,
+            &HashMap::from([((0, 'a'), 1), ((0, 'b'), 1), ((1, 'a'), 0), ((1, 'b'), 0)]),
+            &HashSet::from([0]),
+        );
+        let grammar = CFGrammar::from_str("S\nab\nS->aSb\nS->a\nS->b\nS->\nS")
+            .expect("Failed to parse the grammar.");
+        let intersection = grammar
+            .intersect_regular(&dfa)
+            .expect("Failed to intersect.");
+        let mut parser = EarleyParser::new();
+        let mut other_parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        other_parser.fit(&intersection).expect("Fit unsuccessful");
+
+        for word in ["", "b", "ab", "abb", "aabb", "aaabbb", "aaabb", "ba", "aab"] {
+            assert_eq!(
+                Parser::predict(&mut other_parser, word),
+                Parser::predict(&mut parser, word) && dfa.accepts(word),
+                "{word}"
+            );
+        }
+
+        assert!(Parser::predict(&mut other_parser, "aabb"));
+        assert!(Parser::predict(&mut other_parser, ""));
+        assert!(!Parser::predict(&mut other_parser, "aab"));
+    }
+
+    #[test]
+    fn dfa_unit_test_2() {
+        // Words without two letters 'a' in a row.
+        let dfa = SimpleDfa::new(
+            0,
+            &HashMap::from([((0, 'a'), 1), ((0, 'b'), 0), ((1, 'b'), 0)]),
+            &HashSet::from([0, 1]),
+        );
+        let grammar = CFGrammar::from_str("S\nab\nS->aS\nS->bS\nS->\nS")
+            .expect("Failed to parse the grammar.");
+        let intersection = grammar
+            .intersect_regular(&dfa)
+            .expect("Failed to intersect.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&intersection).expect("Fit unsuccessful");
+
+        for word in ["", "a", "abab", "bbab", "aa", "baab", "abaa"] {
+            assert_eq!(
+                Parser::predict(&mut parser, word),
+                dfa.accepts(word),
+                "{word}"
+            );
+        }
+
+        let empty = SimpleDfa::new(0, &HashMap::new(), &HashSet::new());
+        let intersection = grammar
+            .intersect_regular(&empty)
+            .expect("Failed to intersect.");
+        assert_eq!(intersection.rules().count(), 0);
+    }
+
+    #[test]
+    fn dfa_should_fail_1() {
+        // Doubling modulo 81 plus the letter, which reaches every state from any
+        // other one, leaving too many pairs of states for the symbols.
+        let states = 81;
+        let transitions = (0..states)
+            .flat_map(|state| {
+                [
+                    ((state, 'a'), 2 * state % states),
+                    ((state, 'b'), (2 * state + 1) % states),
+                ]
+            })
+            .collect();
+        let dfa = SimpleDfa::new(0, &transitions, &HashSet::from([0]));
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aS|bS|a|b\nS").expect("Failed to parse the grammar.");
+        let message = format!("{:#}", grammar.intersect_regular(&dfa).unwrap_err());
+        assert!(message.contains("no unused symbols left"));
+    }
+}
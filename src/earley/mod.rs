@@ -1,12 +1,23 @@
+mod sppf;
+mod viterbi;
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
 use anyhow::Context;
 
 use super::*;
 
+pub use sppf::{Sppf, SppfNode};
+pub use viterbi::WeightedEarleyParser;
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
 struct EarleySituation {
     rule: CFRule,
     pos: usize,
     prev_cnt: usize,
+    /// Number of symbols in the right part of the rule.
+    size: usize,
 }
 
 impl EarleySituation {
@@ -15,49 +26,106 @@ impl EarleySituation {
             rule: rule.clone(),
             pos,
             prev_cnt,
+            size: rule.1.chars().count(),
         }
     }
 
+    fn size(&self) -> usize {
+        self.size
+    }
+
     fn nth(&self, pos: usize) -> char {
         self.rule.1.chars().nth(pos).unwrap_or_default()
     }
 }
 
+impl fmt::Display for EarleySituation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let before: String = self.rule.1.chars().take(self.pos).collect();
+        let after: String = self.rule.1.chars().skip(self.pos).collect();
+        write!(
+            f,
+            "{} -> {}·{}, origin={}",
+            self.rule.0.escape_debug(),
+            before.escape_debug(),
+            after.escape_debug(),
+            self.prev_cnt
+        )
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct EarleyParser {
     grammar: Option<CFGrammar>,
     situations: Vec<HashSet<EarleySituation>>,
+    /// Number of leading layers of the chart filled completely, the ones past
+    /// them are missing if the limit stopped the parsing.
+    filled: usize,
+    /// Cleared layers kept to reuse their allocations for longer words.
+    spare: Vec<HashSet<EarleySituation>>,
+    /// Situations of every layer by the symbol after the dot, the current layer
+    /// included as its situations are added.
+    waiting: Vec<HashMap<char, Vec<EarleySituation>>>,
+    /// Non-terminals deriving the empty word.
+    nullable: HashSet<char>,
+    /// Maximal number of situations in the chart of a word.
+    limit: Option<usize>,
+    /// Whether `predict` drops the situations that can not affect later layers.
+    gc: bool,
+    /// Whether some non-terminal of the grammar derives itself.
+    cyclic: bool,
 }
 
 impl Parser for EarleyParser {
     fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        self.reset();
         self.check_grammar(grammar)
             .with_context(|| "The grammar is not context free.")?;
         self.grammar = Some(grammar.clone());
+        self.nullable = grammar.nullable();
+        self.cyclic = grammar.has_cycle();
         Ok(())
     }
 
-    fn predict(&mut self, word: &str) -> bool {
+    /// Build a parse tree of the word, one of many for ambiguous grammars.
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError> {
         if self.grammar.is_none() {
-            return false;
+            return Err(ParseError::NotFitted);
         }
 
-        self.situations.clear();
-        self.situations.resize(word.len() + 1, HashSet::new());
-        let start_rule = self.grammar.as_ref().unwrap().get_start_rule();
-        self.situations[0].insert(EarleySituation::new(&start_rule, 0, 0));
-        self.do_layer(0);
+        self.grammar.as_ref().unwrap().check_word(word)?;
+        let letters: Vec<_> = word.chars().collect();
+        self.reset_chart(0, letters.len());
 
-        for (i, letter) in word.char_indices() {
-            self.scan(letter, i);
-            self.do_layer(i + 1);
+        if !self.predict_from(&letters, 0, false)? {
+            let pos = self.get_furthest();
+            return Err(match letters.get(pos) {
+                Some(found) => ParseError::UnexpectedSymbol { pos, found: *found },
+                None => ParseError::UnexpectedEnd,
+            });
         }
 
-        self.situations[word.len()].contains(&EarleySituation::new(
-            &start_rule,
-            start_rule.1.len(),
-            0,
-        ))
+        let start_rule = self.get_start_rule();
+        let mut visited = HashSet::new();
+
+        match self.build_tree(&letters, &start_rule, 0, letters.len(), &mut visited) {
+            Some(ParseTree::Node { mut children, .. }) => {
+                children.pop().ok_or(ParseError::UnexpectedEnd)
+            }
+            _ => Err(ParseError::UnexpectedEnd),
+        }
+    }
+
+    fn predict(&mut self, word: &str) -> bool {
+        self.predict_tokens(&word.chars().collect::<Vec<_>>())
+    }
+
+    fn reset(&mut self) {
+        *self = Self {
+            limit: self.limit,
+            gc: self.gc,
+            ..Self::new()
+        };
     }
 }
 
@@ -66,12 +134,488 @@ impl EarleyParser {
         Self {
             grammar: None,
             situations: Vec::new(),
+            filled: 0,
+            spare: Vec::new(),
+            waiting: Vec::new(),
+            nullable: HashSet::new(),
+            limit: None,
+            gc: false,
+            cyclic: false,
+        }
+    }
+
+    /// Parser giving up on words whose chart grows past `limit` situations.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Parser keeping only the situations later layers may complete while
+    /// predicting, so the live chart of a long word stays small for the usual
+    /// unambiguous grammars.
+    ///
+    /// Parsing still fills the whole chart. After `predict` the methods looking
+    /// into the chart of the last word only see its last layer.
+    pub fn with_gc(gc: bool) -> Self {
+        Self { gc, ..Self::new() }
+    }
+
+    /// Check if the already tokenized word is in the language.
+    pub fn predict_tokens(&mut self, tokens: &[char]) -> bool {
+        self.fill_chart(tokens, self.gc)
+    }
+
+    fn fill_chart(&mut self, tokens: &[char], gc: bool) -> bool {
+        if self.grammar.is_none() {
+            return false;
+        }
+
+        self.reset_chart(0, tokens.len());
+        self.predict_from(tokens, 0, gc) == Ok(true)
+    }
+
+    /// Check every word, reusing the chart allocations between them.
+    pub fn predict_many(&mut self, words: &[&str]) -> Vec<bool> {
+        let mut tokens = Vec::new();
+
+        words
+            .iter()
+            .map(|word| {
+                tokens.clear();
+                tokens.extend(word.chars());
+                self.predict_tokens(&tokens)
+            })
+            .collect()
+    }
+
+    /// Check if the word is in the language, reusing the chart of the previously
+    /// parsed word. The caller guarantees that both words share the first
+    /// `changed_from` letters, since the chart is only recomputed past them.
+    pub fn predict_incremental(&mut self, word: &str, changed_from: usize) -> bool {
+        if self.grammar.is_none() {
+            return false;
+        }
+
+        let letters: Vec<_> = word.chars().collect();
+
+        // The collected chart can not be reused.
+        if self.gc {
+            return self.fill_chart(&letters, true);
+        }
+
+        let from = changed_from
+            .min(letters.len())
+            .min(self.filled.saturating_sub(1));
+        self.reset_chart(from, letters.len());
+        self.predict_from(&letters, from, false) == Ok(true)
+    }
+
+    /// Make the chart have a layer for every position of a word of the length,
+    /// clearing the layers past `from`. The first layer only depends on the
+    /// grammar, so it is kept as well.
+    fn reset_chart(&mut self, from: usize, len: usize) {
+        while self.situations.len() > len + 1 {
+            let mut layer = self.situations.pop().unwrap();
+            layer.clear();
+            self.spare.push(layer);
+        }
+
+        for layer in self.situations.iter_mut().skip(from + 1) {
+            layer.clear();
+        }
+
+        while self.situations.len() < len + 1 {
+            self.situations.push(self.spare.pop().unwrap_or_default());
         }
     }
 
+    /// Fill the chart for the letters from the position on, keeping earlier layers.
+    /// The limit is checked after every layer, the first one included.
+    ///
+    /// With `gc` a layer is dropped once the next one is done, and the waiting
+    /// situations no later completion can reach are dropped whenever their number
+    /// doubles since the last collection.
+    fn predict_from(&mut self, tokens: &[char], from: usize, gc: bool) -> Result<bool, ParseError> {
+        let start_rule = self.get_start_rule();
+
+        if self.situations[0].is_empty() {
+            self.situations[0].insert(EarleySituation::new(&start_rule, 0, 0));
+            self.do_layer(0);
+        }
+
+        self.filled = from + 1;
+        let mut steps: usize = self.situations[..=from].iter().map(HashSet::len).sum();
+        let mut waiting_layers = Vec::new();
+        let mut waiting_cnt = 0;
+        let mut collected_cnt = 0;
+
+        for (i, letter) in tokens.iter().enumerate().skip(from) {
+            if self.limit.is_some_and(|limit| steps > limit) {
+                return Err(ParseError::LimitExceeded);
+            }
+
+            self.scan(*letter, i);
+            self.do_layer(i + 1);
+            self.filled = i + 2;
+            steps += self.situations[i + 1].len();
+
+            // An empty layer is kept for the furthest position to be found.
+            if gc && !self.situations[i + 1].is_empty() {
+                self.situations[i] = HashSet::new();
+                let grammar = self.grammar.as_ref().unwrap();
+                self.waiting[i].retain(|symbol, _| grammar.is_non_terminal(symbol));
+
+                if !self.waiting[i].is_empty() {
+                    waiting_layers.push(i);
+                    waiting_cnt += self.waiting[i].values().map(Vec::len).sum::<usize>();
+                }
+
+                if waiting_cnt > 2 * collected_cnt + 64 {
+                    waiting_cnt = self.collect_waiting(i + 1, &mut waiting_layers);
+                    collected_cnt = waiting_cnt;
+                }
+            }
+        }
+
+        if self.limit.is_some_and(|limit| steps > limit) {
+            return Err(ParseError::LimitExceeded);
+        }
+
+        Ok(
+            self.situations[tokens.len()].contains(&EarleySituation::new(
+                &start_rule,
+                start_rule.1.chars().count(),
+                0,
+            )),
+        )
+    }
+
+    /// Check if the word is in the language, returning the furthest position the
+    /// parsing reached otherwise (the word length if it ended unexpectedly).
+    /// There is no position if the parser is not fitted or hit its limit.
+    pub fn predict_at(&mut self, word: &str) -> Result<(), Option<usize>> {
+        if self.grammar.is_none() {
+            return Err(None);
+        }
+
+        let letters: Vec<_> = word.chars().collect();
+        self.reset_chart(0, letters.len());
+
+        match self.predict_from(&letters, 0, self.gc) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(Some(self.get_furthest())),
+            Err(_) => Err(None),
+        }
+    }
+
+    /// Same as `predict_at` with the position translated into a line and a
+    /// column of the word.
+    pub fn predict_position(&mut self, word: &str) -> Result<(), Option<Position>> {
+        self.predict_at(word)
+            .map_err(|index| index.map(|index| Position::at(word, index)))
+    }
+
+    /// Last layer of the chart the parsing reached.
+    fn get_furthest(&self) -> usize {
+        self.situations
+            .iter()
+            .rposition(|situations| !situations.is_empty())
+            .unwrap_or_default()
+    }
+
+    /// Check if the word can be extended to a word in the language, assuming all
+    /// non-terminals of the grammar are productive.
+    pub fn is_valid_prefix(&mut self, word: &str) -> bool {
+        let letters: Vec<_> = word.chars().collect();
+        self.predict_tokens(&letters);
+        self.situations
+            .get(letters.len())
+            .is_some_and(|situations| !situations.is_empty())
+    }
+
+    /// Check the word either as a whole or as a prefix.
+    pub fn matches(&mut self, word: &str, mode: MatchMode) -> bool {
+        match mode {
+            MatchMode::Exact => Parser::predict(self, word),
+            MatchMode::Prefix => self.is_valid_prefix(word),
+        }
+    }
+
+    /// Situations of every layer of the last parsed word's chart, sorted.
+    pub fn chart_to_string(&self) -> String {
+        let mut chart = String::new();
+
+        for (i, situations) in self.situations.iter().enumerate() {
+            let mut items: Vec<_> = situations.iter().map(ToString::to_string).collect();
+            items.sort();
+            chart += &format!("Set {i}:\n");
+
+            for item in items {
+                chart += &format!("    {item}\n");
+            }
+        }
+
+        chart
+    }
+
+    /// Terminals allowing the last parsed word to continue at the position, with
+    /// `END_TERMINAL` if the word could end there.
+    pub fn expected_at(&self, pos: usize) -> HashSet<char> {
+        let Some(grammar) = self.grammar.as_ref() else {
+            return HashSet::new();
+        };
+        let mut expected = HashSet::new();
+
+        for situation in self.situations.get(pos).into_iter().flatten() {
+            if situation.pos < situation.size() {
+                let symbol = situation.nth(situation.pos);
+
+                if !grammar.is_non_terminal(&symbol) {
+                    expected.insert(symbol);
+                }
+            } else if situation.rule.0 == START_RULE && situation.prev_cnt == 0 {
+                expected.insert(END_TERMINAL);
+            }
+        }
+
+        expected
+    }
+
+    /// Describe why the word is rejected: the letter the parsing stopped at and
+    /// the terminals that could have continued the word there.
+    pub fn explain(&mut self, word: &str) -> Result<(), String> {
+        if self.grammar.is_none() {
+            return Err(ParseError::NotFitted.to_string());
+        }
+
+        let letters: Vec<_> = word.chars().collect();
+        self.reset_chart(0, letters.len());
+
+        match self.predict_from(&letters, 0, false) {
+            Ok(true) => return Ok(()),
+            Ok(false) => {}
+            Err(err) => return Err(err.to_string()),
+        }
+
+        let pos = self.get_furthest();
+        let expected = self.expected_at(pos);
+        let mut expected_letters: Vec<_> = expected
+            .iter()
+            .filter(|symbol| **symbol != END_TERMINAL)
+            .collect();
+        expected_letters.sort();
+        let mut names: Vec<_> = expected_letters
+            .iter()
+            .map(|symbol| match **symbol {
+                ANY_TERMINAL => "any letter".to_string(),
+                _ => symbol.to_string(),
+            })
+            .collect();
+
+        if expected.contains(&END_TERMINAL) {
+            names.push("end of the word".to_string());
+        }
+
+        let found = match letters.get(pos) {
+            Some(found) => format!("Unexpected {found:?} at position {pos}"),
+            None => format!("Unexpected end of the word at position {pos}"),
+        };
+
+        if names.is_empty() {
+            Err(format!("{found}."))
+        } else {
+            Err(format!(
+                "{found}, expected one of {{{}}}.",
+                names.join(", ")
+            ))
+        }
+    }
+
+    /// Non-terminals deriving a part of the last parsed word ending at the
+    /// position, with the positions the parts start at, sorted.
+    pub fn completions(&self, pos: usize) -> Vec<(char, usize)> {
+        let mut completions: Vec<_> = self
+            .situations
+            .get(pos)
+            .into_iter()
+            .flatten()
+            .filter(|situation| situation.pos == situation.size() && situation.rule.0 != START_RULE)
+            .map(|situation| (situation.rule.0, situation.prev_cnt))
+            .collect();
+        completions.sort();
+        completions.dedup();
+        completions
+    }
+
+    /// Rules applied in a leftmost derivation of the word from the start.
+    pub fn leftmost_derivation(&mut self, word: &str) -> Option<Vec<CFRule>> {
+        let tree = Parser::parse(self, word).ok()?;
+        let mut rules = Vec::new();
+        let mut stack = vec![&tree];
+
+        while let Some(node) = stack.pop() {
+            if let ParseTree::Node { rule, children } = node {
+                rules.push(rule.clone());
+                stack.extend(children.iter().rev());
+            }
+        }
+
+        Some(rules)
+    }
+
+    /// Check if the word is in the language, calling `on_complete(rule, start,
+    /// end)` for every rule the parser completed on `word[start..end]`, the ones
+    /// ending earlier first and the rules of a span before the rules using them.
+    ///
+    /// The callback is called for every completion in the chart, so for ambiguous
+    /// grammars a part of the word may be reported several times, and the parts
+    /// no parse of the whole word uses are reported as well.
+    pub fn predict_with<F>(&mut self, word: &str, mut on_complete: F) -> bool
+    where
+        F: FnMut(&CFRule, usize, usize),
+    {
+        let letters: Vec<_> = word.chars().collect();
+
+        // The first layer is kept between words, its completions must be redone.
+        if let Some(first) = self.situations.first_mut() {
+            first.clear();
+        }
+
+        let accepted = self.fill_chart(&letters, false);
+
+        for (end, situations) in self.situations.iter().enumerate() {
+            let mut completed: Vec<_> = situations
+                .iter()
+                .filter(|situation| {
+                    situation.pos == situation.size() && situation.rule.0 != START_RULE
+                })
+                .collect();
+            completed.sort_by(|a, b| {
+                b.prev_cnt
+                    .cmp(&a.prev_cnt)
+                    .then_with(|| a.rule.cmp(&b.rule))
+            });
+
+            for span in completed.chunk_by(|a, b| a.prev_cnt == b.prev_cnt) {
+                for situation in get_span_order(span) {
+                    on_complete(&situation.rule, situation.prev_cnt, end);
+                }
+            }
+        }
+
+        accepted
+    }
+
+    /// Number of distinct parse trees of the word, 0 if it is rejected.
+    ///
+    /// Fails for cyclic grammars, whose words may have infinitely many trees, and
+    /// when the number does not fit into `usize`.
+    pub fn parse_count(&mut self, word: &str) -> Result<usize, anyhow::Error> {
+        if self.cyclic {
+            bail!("The grammar is cyclic, the number of parse trees may be infinite.");
+        }
+
+        match self.parse_forest(word).map(|forest| forest.count()) {
+            None => Ok(0),
+            Some(Some(count)) => Ok(count),
+            Some(None) => bail!("The number of parse trees does not fit into usize."),
+        }
+    }
+
+    fn build_tree(
+        &self,
+        letters: &[char],
+        rule: &CFRule,
+        origin: usize,
+        end: usize,
+        visited: &mut HashSet<(CFRule, usize, usize)>,
+    ) -> Option<ParseTree> {
+        let key = (rule.clone(), origin, end);
+
+        if !visited.insert(key.clone()) {
+            return None;
+        }
+
+        let situation = EarleySituation::new(rule, rule.1.chars().count(), origin);
+        let children = self.build_children(letters, &situation, end, visited);
+        visited.remove(&key);
+        children.map(|children| ParseTree::Node {
+            rule: rule.clone(),
+            children,
+        })
+    }
+
+    fn build_children(
+        &self,
+        letters: &[char],
+        situation: &EarleySituation,
+        end: usize,
+        visited: &mut HashSet<(CFRule, usize, usize)>,
+    ) -> Option<Vec<ParseTree>> {
+        if situation.pos == 0 {
+            return if end == situation.prev_cnt {
+                Some(Vec::new())
+            } else {
+                None
+            };
+        }
+
+        let symbol = situation.nth(situation.pos - 1);
+        let prev_situation =
+            EarleySituation::new(&situation.rule, situation.pos - 1, situation.prev_cnt);
+
+        if !self.grammar.as_ref().unwrap().is_non_terminal(&symbol) {
+            if end == 0
+                || (letters[end - 1] != symbol && symbol != ANY_TERMINAL)
+                || !self.situations[end - 1].contains(&prev_situation)
+            {
+                return None;
+            }
+
+            let mut children = self.build_children(letters, &prev_situation, end - 1, visited)?;
+            children.push(ParseTree::Leaf(letters[end - 1]));
+            return Some(children);
+        }
+
+        for completed in self.situations[end].iter() {
+            if completed.rule.0 != symbol || completed.pos != completed.size() {
+                continue;
+            }
+
+            let middle = completed.prev_cnt;
+
+            if middle < situation.prev_cnt || !self.situations[middle].contains(&prev_situation) {
+                continue;
+            }
+
+            if let Some(child) = self.build_tree(letters, &completed.rule, middle, end, visited) {
+                if let Some(mut children) =
+                    self.build_children(letters, &prev_situation, middle, visited)
+                {
+                    children.push(child);
+                    return Some(children);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Start rule of the fitted grammar, checked to be unique in `fit`.
+    fn get_start_rule(&self) -> CFRule {
+        self.grammar
+            .as_ref()
+            .and_then(|grammar| grammar.get_start_rule().ok())
+            .unwrap_or_default()
+    }
+
     fn check_grammar(&self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        grammar.get_start_rule()?;
+
         for rule in grammar.rules.iter() {
-            if !grammar.non_terminals.contains(&rule.0) {
+            if !grammar.non_terminals.contains(rule.0) {
                 bail!("There must be no terminals in the left part of the CF grammar rule");
             }
         }
@@ -83,7 +627,7 @@ impl EarleyParser {
         let mut new_situations = HashSet::<EarleySituation>::new();
 
         for situation in self.situations[curr_cnt].iter() {
-            let rule_left = if situation.pos >= situation.rule.1.len() {
+            let rule_left = if situation.pos >= situation.size() {
                 continue;
             } else {
                 situation.nth(situation.pos)
@@ -98,22 +642,42 @@ impl EarleyParser {
                     ));
                 }
             }
+
+            // Aycock-Horspool: a nullable non-terminal may be skipped right away.
+            if self.nullable.contains(&rule_left) {
+                new_situations.insert(EarleySituation::new(
+                    &situation.rule,
+                    situation.pos + 1,
+                    situation.prev_cnt,
+                ));
+            }
         }
 
-        self.situations[curr_cnt].extend(new_situations);
+        self.add_situations(curr_cnt, new_situations);
     }
 
     fn scan(&mut self, letter: char, curr_cnt: usize) {
         let mut new_situations = HashSet::<EarleySituation>::new();
 
+        let Some(grammar) = self.grammar.as_ref() else {
+            return;
+        };
+        let known = letter != ANY_TERMINAL && grammar.is_terminal(&letter);
+
+        // Unknown letters and non-terminals leave the next layer empty, unless
+        // the wildcard matches them.
+        if !known && !grammar.is_terminal(&ANY_TERMINAL) {
+            return;
+        }
+
         for situation in self.situations[curr_cnt].iter() {
-            let rule_curr = if situation.pos >= situation.rule.1.len() {
+            let rule_curr = if situation.pos >= situation.size() {
                 continue;
             } else {
                 situation.nth(situation.pos)
             };
 
-            if rule_curr == letter {
+            if (known && rule_curr == letter) || rule_curr == ANY_TERMINAL {
                 new_situations.insert(EarleySituation::new(
                     &situation.rule,
                     situation.pos + 1,
@@ -129,25 +693,96 @@ impl EarleyParser {
         let mut new_situations = HashSet::<EarleySituation>::new();
 
         for curr_situation in self.situations[curr_cnt].iter() {
-            if curr_situation.pos != curr_situation.rule.1.len() {
+            if curr_situation.pos != curr_situation.size() {
                 continue;
             }
 
-            for prev_situation in self.situations[curr_situation.prev_cnt].iter() {
-                if prev_situation.nth(prev_situation.pos) == curr_situation.rule.0 {
-                    new_situations.insert(EarleySituation::new(
-                        &prev_situation.rule,
-                        prev_situation.pos + 1,
-                        prev_situation.prev_cnt,
-                    ));
+            let prev_situations = self.waiting[curr_situation.prev_cnt]
+                .get(&curr_situation.rule.0)
+                .into_iter()
+                .flatten();
+
+            for prev_situation in prev_situations {
+                new_situations.insert(EarleySituation::new(
+                    &prev_situation.rule,
+                    prev_situation.pos + 1,
+                    prev_situation.prev_cnt,
+                ));
+            }
+        }
+
+        self.add_situations(curr_cnt, new_situations);
+    }
+
+    /// Insert the situations into the layer, indexing the new ones by the symbol
+    /// after the dot.
+    fn add_situations(&mut self, layer: usize, situations: HashSet<EarleySituation>) {
+        for situation in situations {
+            if self.situations[layer].contains(&situation) {
+                continue;
+            }
+
+            if situation.pos < situation.size() {
+                self.waiting[layer]
+                    .entry(situation.nth(situation.pos))
+                    .or_default()
+                    .push(situation.clone());
+            }
+
+            self.situations[layer].insert(situation);
+        }
+    }
+
+    /// Drop the waiting situations of the layers that no situation of the current
+    /// layer leads to by their origins. The layers with waiting situations are
+    /// narrowed down to the kept ones, whose number is returned.
+    fn collect_waiting(&mut self, curr_cnt: usize, layers: &mut Vec<usize>) -> usize {
+        let mut live: BTreeSet<_> = self.situations[curr_cnt]
+            .iter()
+            .map(|situation| situation.prev_cnt)
+            .filter(|origin| *origin < curr_cnt)
+            .collect();
+        let mut pending = live.clone();
+
+        // Origins never exceed the layer, so the layers are visited downwards.
+        while let Some(layer) = pending.pop_last() {
+            for situation in self.waiting[layer].values().flatten() {
+                if live.insert(situation.prev_cnt) {
+                    pending.insert(situation.prev_cnt);
                 }
             }
         }
 
-        self.situations[curr_cnt].extend(new_situations);
+        layers.retain(|layer| {
+            if !live.contains(layer) {
+                self.waiting[*layer] = HashMap::new();
+            }
+
+            live.contains(layer)
+        });
+        layers
+            .iter()
+            .map(|layer| self.waiting[*layer].values().map(Vec::len).sum::<usize>())
+            .sum()
     }
 
+    /// Close the layer under prediction and completion, indexing the scanned
+    /// situations first and the others as they are added.
     fn do_layer(&mut self, layer: usize) {
+        let mut waiting = HashMap::<char, Vec<EarleySituation>>::new();
+
+        for situation in self.situations[layer].iter() {
+            if situation.pos < situation.size() {
+                waiting
+                    .entry(situation.nth(situation.pos))
+                    .or_default()
+                    .push(situation.clone());
+            }
+        }
+
+        self.waiting.truncate(layer);
+        self.waiting.push(waiting);
+
         loop {
             let prev_size = self.situations[layer].len();
             self.predict(layer);
@@ -160,6 +795,27 @@ impl EarleyParser {
     }
 }
 
+/// Completed situations of the same span with the ones whose left part is used
+/// by the right part of another going first. Cycles are broken arbitrarily.
+fn get_span_order<'a>(span: &[&'a EarleySituation]) -> Vec<&'a EarleySituation> {
+    let mut pending = span.to_vec();
+    let mut order = Vec::new();
+
+    while !pending.is_empty() {
+        let ready = pending
+            .iter()
+            .position(|situation| {
+                !pending.iter().any(|other| {
+                    other.rule.0 != situation.rule.0 && situation.rule.1.contains(other.rule.0)
+                })
+            })
+            .unwrap_or_default();
+        order.push(pending.remove(ready));
+    }
+
+    order
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,7 +825,7 @@ mod tests {
         let grammar = get_test_grammar();
         let mut parser = EarleyParser::new();
         parser.fit(&grammar).expect("Fit unsuccessful");
-        assert_eq!(Parser::predict(&mut parser, "(a+a)"), true);
+        assert!(Parser::predict(&mut parser, "(a+a)"));
     }
 
     #[test]
@@ -177,21 +833,538 @@ mod tests {
         let grammar = get_test_grammar();
         let mut parser = EarleyParser::new();
         parser.fit(&grammar).expect("Fit unsuccessful");
-        assert_eq!(Parser::predict(&mut parser, "(a+a*a())"), false);
+        assert!(!Parser::predict(&mut parser, "(a+a*a())"));
+    }
+
+    #[test]
+    fn earley_epsilon_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "aabb"));
+        assert!(Parser::predict(&mut parser, ""));
+        assert!(!Parser::predict(&mut parser, "aab"));
+        assert!(!Parser::predict(&mut parser, "abab"));
+        assert!(parser.parse("aabb").is_ok());
+    }
+
+    #[test]
+    fn earley_epsilon_test_2() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->ABAb\nA->B\nB->\nB->a\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "b"));
+        assert!(Parser::predict(&mut parser, "aab"));
+        assert!(!Parser::predict(&mut parser, "aaaab"));
+    }
+
+    #[test]
+    fn earley_predict_at_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.predict_at("(a+a)"), Ok(()));
+        assert_eq!(parser.predict_at("(a+a*a())"), Err(Some(6)));
+        assert_eq!(parser.predict_at("a+"), Err(Some(2)));
+        assert_eq!(parser.predict_at(")"), Err(Some(0)));
+        assert_eq!(EarleyParser::new().predict_at("a"), Err(None));
+    }
+
+    #[test]
+    fn earley_predict_position_test_1() {
+        let grammar = CFGrammarBuilder::new()
+            .rule('S', "")
+            .rule('S', "a\nS")
+            .start('S')
+            .build()
+            .expect("Failed to build the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.predict_position("a\na\n"), Ok(()));
+        assert_eq!(
+            parser.predict_position("a\na\naa\n"),
+            Err(Some(Position { line: 2, col: 1 }))
+        );
+    }
+
+    #[test]
+    fn earley_parse_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let node = |rule: (char, &str), children| ParseTree::Node {
+            rule: (rule.0, rule.1.to_string()),
+            children,
+        };
+        let tree = node(
+            ('S', "N"),
+            vec![node(
+                ('N', "T"),
+                vec![node(
+                    ('T', "F"),
+                    vec![node(('F', "a"), vec![ParseTree::Leaf('a')])],
+                )],
+            )],
+        );
+        assert_eq!(parser.parse("a"), Ok(tree));
+    }
+
+    #[test]
+    fn earley_parse_test_2() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let tree = parser.parse("(a+a)*a").expect("Parse unsuccessful");
+        assert_eq!(get_leaves(&tree), "(a+a)*a");
+        assert_eq!(
+            parser.parse("(a+a*a())"),
+            Err(ParseError::UnexpectedSymbol { pos: 6, found: '(' })
+        );
+        assert_eq!(parser.parse("(a+a"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(EarleyParser::new().parse("a"), Err(ParseError::NotFitted));
+    }
+
+    #[test]
+    fn earley_tokens_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(parser.predict_tokens(&['a', '+', 'a']));
+        assert!(!parser.predict_tokens(&['a', 'a']));
+    }
+
+    #[test]
+    fn earley_expected_at_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.predict_at("a+)"), Err(Some(2)));
+        assert_eq!(parser.expected_at(2), HashSet::from(['a', '(']));
+        assert_eq!(
+            parser.expected_at(1),
+            HashSet::from(['+', '*', END_TERMINAL])
+        );
+        assert!(parser.expected_at(3).is_empty());
+    }
+
+    #[test]
+    fn earley_start_test_1() {
+        let grammar = get_test_grammar()
+            .with_start('T')
+            .expect("Failed to change the start.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a*(a+a)"));
+        assert!(!Parser::predict(&mut parser, "a+a"));
+    }
+
+    #[test]
+    fn earley_refit_test_1() {
+        let mut parser = EarleyParser::new();
+        parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a+a"));
+        let grammar =
+            CFGrammar::from_str("S\nb\nS->bS\nS->b\nS").expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "bbb"));
+        assert!(!Parser::predict(&mut parser, "a+a"));
+        assert!(!Parser::predict(&mut parser, "a"));
+    }
+
+    #[test]
+    fn earley_explain_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        assert_eq!(
+            parser.explain("a"),
+            Err("The parser is not fitted to a grammar.".to_string())
+        );
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.explain("(a+a)*a"), Ok(()));
+        assert_eq!(
+            parser.explain("a+)"),
+            Err("Unexpected ')' at position 2, expected one of {(, a}.".to_string())
+        );
+        assert_eq!(
+            parser.explain("(a"),
+            Err("Unexpected end of the word at position 2, expected one of {), *, +}.".to_string())
+        );
+        assert_eq!(
+            parser.explain("ax"),
+            Err(
+                "Unexpected 'x' at position 1, expected one of {*, +, end of the word}."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn earley_incremental_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "(a+a)*a"));
+        assert!(!parser.predict_incremental("(a+a)*", 6));
+        assert!(parser.predict_incremental("(a+a)*(a)", 6));
+        assert!(parser.predict_incremental("(a*a)*(a)", 2));
+        assert!(!parser.predict_incremental("(a*a+", 4));
+        assert!(parser.predict_incremental("(a*a+a)", 5));
+
+        for word in ["a", "a+a*a", "(a", "a+(a*a)"] {
+            let expected = Parser::predict(&mut parser, word);
+            assert!(Parser::predict(&mut parser, "a*a"));
+            assert_eq!(parser.predict_incremental(word, 1), expected);
+        }
+    }
+
+    #[test]
+    fn earley_incremental_test_2() {
+        let grammar =
+            CFGrammar::from_str("S\na\nS->aS|a\nS").expect("Failed to parse the grammar.");
+
+        // The chart of the word stopped by the limit is only filled partially.
+        for limit in [12, 32] {
+            let mut parser = EarleyParser::with_limit(limit);
+            let mut other_parser = EarleyParser::with_limit(limit);
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            other_parser.fit(&grammar).expect("Fit unsuccessful");
+            assert!(!Parser::predict(&mut parser, &"a".repeat(20)));
+
+            for (word, changed_from) in [("aaaa", 3), ("aaaaaaaa", 7), ("aa", 1)] {
+                assert_eq!(
+                    parser.predict_incremental(word, changed_from),
+                    Parser::predict(&mut other_parser, word),
+                    "{limit} {word}"
+                );
+                assert_eq!(parser.chart_to_string(), other_parser.chart_to_string());
+                assert!(!Parser::predict(&mut parser, &"a".repeat(20)));
+            }
+        }
+    }
+
+    #[test]
+    fn earley_match_mode_test_1() {
+        let mut parser = EarleyParser::new();
+        parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+
+        for (word, exact, prefix) in [
+            ("a+a", true, true),
+            ("(a*", false, true),
+            ("", false, true),
+            ("a)", false, false),
+        ] {
+            assert_eq!(parser.matches(word, MatchMode::Exact), exact, "{word}");
+            assert_eq!(parser.matches(word, MatchMode::Prefix), prefix, "{word}");
+        }
+    }
+
+    #[test]
+    fn earley_prefix_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        assert!(!parser.is_valid_prefix(""));
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["", "(", "a+", "(a*(a", "a+a"] {
+            assert!(parser.is_valid_prefix(word));
+        }
+
+        for word in [")", "a(", "(a+)", "a++"] {
+            assert!(!parser.is_valid_prefix(word));
+        }
     }
 
-    fn get_test_grammar() -> CFGrammar {
-        let terminals = HashSet::from(['a', '+', '*', '(', ')']);
-        let non_terminals = HashSet::from(['S', 'T', 'F', 'N']);
-        let mut rules = MultiMap::new();
-        rules.insert('S', "N".to_string());
-        rules.insert('N', "T+N".to_string());
-        rules.insert('N', "T".to_string());
-        rules.insert('T', "F*T".to_string());
-        rules.insert('T', "F".to_string());
-        rules.insert('F', "(N)".to_string());
-        rules.insert('F', "a".to_string());
-        let start = 'S';
-        CFGrammar::new(&terminals, &non_terminals, &rules, start)
+    #[test]
+    fn earley_chart_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a"));
+        let chart = parser.chart_to_string();
+        assert!(chart.starts_with("Set 0:\n"));
+        assert!(chart.contains("Set 1:\n"));
+        assert!(!chart.contains("Set 2:"));
+        assert!(chart.contains("    F -> ·a, origin=0\n"));
+        assert!(chart.contains("    F -> a·, origin=0\n"));
+        assert_eq!(chart, parser.chart_to_string());
+    }
+
+    #[test]
+    fn earley_derivation_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let rules = parser
+            .leftmost_derivation("a+a")
+            .expect("Parse unsuccessful");
+        let expected = [
+            ('S', "N"),
+            ('N', "T+N"),
+            ('T', "F"),
+            ('F', "a"),
+            ('N', "T"),
+            ('T', "F"),
+            ('F', "a"),
+        ];
+        assert_eq!(
+            rules,
+            expected.map(|(rule_left, rule_right)| (rule_left, rule_right.to_string()))
+        );
+        assert!(parser.leftmost_derivation("a+").is_none());
+    }
+
+    #[test]
+    fn earley_parse_count_test_1() {
+        let grammar =
+            CFGrammar::from_str("E\n+a\nE->E+E\nE->a\nE").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.parse_count("a").unwrap(), 1);
+        assert_eq!(parser.parse_count("a+a+a").unwrap(), 2);
+        assert_eq!(parser.parse_count("a+a+a+a").unwrap(), 5);
+        assert_eq!(parser.parse_count("a+").unwrap(), 0);
+        assert!(parser.parse_count(&("a+".repeat(40) + "a")).is_err());
+        let grammar = CFGrammar::from_str("SAB\na\nS->A\nA->B\nA->a\nB->A\nS")
+            .expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a"));
+        assert!(parser.parse_count("a").is_err());
+    }
+
+    fn get_leaves(tree: &ParseTree) -> String {
+        match tree {
+            ParseTree::Leaf(letter) => letter.to_string(),
+            ParseTree::Node { children, .. } => children.iter().map(get_leaves).collect(),
+        }
+    }
+
+    #[test]
+    fn earley_should_fail_1() {
+        let mut grammar = get_test_grammar();
+        grammar.rules.insert(START_RULE, "T".to_string());
+        let mut parser = EarleyParser::new();
+        assert!(parser.fit(&grammar).is_err());
+        assert!(!Parser::predict(&mut parser, "a"));
+        grammar.rules.remove(&START_RULE);
+        assert!(parser.fit(&grammar).is_err());
+    }
+
+    #[test]
+    fn earley_should_fail_3() {
+        let mut grammar = get_test_grammar();
+        let mut parser = EarleyParser::with_limit(100);
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a"));
+        grammar.rules.insert(START_RULE, "T".to_string());
+        assert!(parser.fit(&grammar).is_err());
+        assert!(!Parser::predict(&mut parser, "a"));
+        assert_eq!(parser.parse("a"), Err(ParseError::NotFitted));
+        assert_eq!(parser.limit, Some(100));
+    }
+
+    #[test]
+    fn earley_many_test_1() {
+        let mut parser = EarleyParser::new();
+        parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+        let words = ["a+a", "(a*a)+a", "", "a+", "((a))", "a)"];
+        let expected: Vec<_> = words
+            .iter()
+            .map(|word| Parser::predict(&mut parser, word))
+            .collect();
+        assert_eq!(parser.predict_many(&words), expected);
+        assert_eq!(parser.predict_many(&words), expected);
+        assert_eq!(expected, vec![true, true, false, false, true, false]);
+        parser
+            .fit(&CFGrammar::from_str("S\nb\nS->b\nS").expect("Failed to parse the grammar."))
+            .expect("Fit unsuccessful");
+        assert_eq!(parser.predict_many(&["a", "b"]), vec![false, true]);
+    }
+
+    #[test]
+    fn earley_completions_test_1() {
+        let mut parser = EarleyParser::new();
+        parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+        assert!(!Parser::predict(&mut parser, "(a+a"));
+        assert_eq!(parser.completions(0), vec![]);
+        assert_eq!(parser.completions(2), vec![('F', 1), ('N', 1), ('T', 1)]);
+        assert_eq!(
+            parser.completions(4),
+            vec![('F', 3), ('N', 1), ('N', 3), ('T', 3)]
+        );
+        assert_eq!(parser.completions(5), vec![]);
+        assert!(Parser::predict(&mut parser, "a"));
+        assert_eq!(
+            parser.completions(1),
+            vec![('F', 0), ('N', 0), ('S', 0), ('T', 0)]
+        );
+    }
+
+    #[test]
+    fn earley_unicode_test_1() {
+        let grammar = CFGrammar::from_str("Sλ\nαβ😀\nS->αSβ|λ\nλ->😀|\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["", "😀", "αβ", "αα😀ββ"] {
+            assert!(Parser::predict(&mut parser, word));
+        }
+
+        for word in ["α", "αβ😀", "αλβ"] {
+            assert!(!Parser::predict(&mut parser, word));
+        }
+
+        let tree = parser.parse("α😀β").expect("Parse unsuccessful");
+        assert_eq!(get_leaves(&tree), "α😀β");
+        assert_eq!(parser.predict_at("α😀😀"), Err(Some(2)));
+        assert_eq!(parser.parse_count("αβ").unwrap(), 1);
+    }
+
+    #[test]
+    fn earley_should_fail_2() {
+        let mut parser = EarleyParser::new();
+        parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+        assert!(!Parser::predict(&mut parser, "N"));
+        assert!(!Parser::predict(&mut parser, "a+T"));
+        assert_eq!(parser.predict_at("a+b"), Err(Some(2)));
+        assert_eq!(
+            parser.parse("a+T*a"),
+            Err(ParseError::UnexpectedSymbol { pos: 2, found: 'T' })
+        );
+    }
+
+    #[test]
+    fn earley_wildcard_test_1() {
+        let grammar = CFGrammar::from_str("S\n\nS->.S|.\nS").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["a", "S", "xy.", "любое"] {
+            assert!(Parser::predict(&mut parser, word), "{word}");
+        }
+
+        assert!(!Parser::predict(&mut parser, ""));
+        assert_eq!(
+            parser.parse("x"),
+            Ok(ParseTree::Node {
+                rule: ('S', ANY_TERMINAL.to_string()),
+                children: vec![ParseTree::Leaf('x')],
+            })
+        );
+
+        let grammar =
+            CFGrammar::from_str("S\na.\nS->a.S|a\nS").expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a.a"));
+        assert!(!Parser::predict(&mut parser, "aba"));
+    }
+
+    #[test]
+    fn earley_gc_test_1() {
+        let mut parser = EarleyParser::with_gc(true);
+        let mut other_parser = EarleyParser::new();
+        let grammar = get_test_grammar();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        other_parser.fit(&grammar).expect("Fit unsuccessful");
+        let mut rng = XorShiftRng::new(7);
+
+        let words: Vec<_> = std::iter::repeat_with(|| grammar.sample(&mut rng, 8))
+            .flatten()
+            .take(200)
+            .collect();
+
+        for word in words {
+            let mut words = vec![word.clone(), format!("{word}+"), format!("({word}")];
+            words.push(word.replacen('a', "aa", 1));
+
+            for word in words {
+                assert_eq!(
+                    Parser::predict(&mut parser, &word),
+                    Parser::predict(&mut other_parser, &word),
+                    "{word}"
+                );
+                assert_eq!(parser.predict_at(&word), other_parser.predict_at(&word));
+            }
+        }
+
+        let grammar =
+            CFGrammar::from_str("ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE")
+                .expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let word = "a+a*(a+a)".repeat(500) + "a";
+        let word = word.replace(")a", ")+a");
+        assert!(Parser::predict(&mut parser, &word));
+        let live: usize = parser.situations.iter().map(HashSet::len).sum::<usize>()
+            + parser
+                .waiting
+                .iter()
+                .flat_map(HashMap::values)
+                .map(Vec::len)
+                .sum::<usize>();
+        assert!(live < 200, "{live}");
+        assert!(parser.parse("a+a*(a+a)").is_ok());
+        parser.reset();
+        assert!(parser.gc);
+    }
+
+    #[test]
+    fn earley_predict_with_test_1() {
+        let grammar = CFGrammar::from_str("ET\n+a\nE->E+T\nE->T\nT->a\nE")
+            .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let mut completed = Vec::new();
+        assert!(parser.predict_with("a+a", |rule, start, end| {
+            completed.push((rule.0, rule.1.clone(), start, end))
+        }));
+        let expected = [
+            ('T', "a", 0, 1),
+            ('E', "T", 0, 1),
+            ('T', "a", 2, 3),
+            ('E', "E+T", 0, 3),
+        ];
+        assert_eq!(
+            completed,
+            expected.map(|(left, right, start, end)| (left, right.to_string(), start, end))
+        );
+
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->\nB->A\nS")
+            .expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let mut completed = Vec::new();
+        assert!(parser.predict_with("", |rule, _, _| completed.push(rule.0)));
+        assert_eq!(completed, ['A', 'B', 'S']);
+
+        // Both ways to group the sums are reported, while every tree has 5 nodes.
+        let grammar =
+            CFGrammar::from_str("E\n+a\nE->E+E\nE->a\nE").expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let mut count = 0;
+        assert!(parser.predict_with("a+a+a", |_, _, _| count += 1));
+        assert_eq!(count, 6);
+        count = 0;
+        assert!(!parser.predict_with("a+a+", |_, _, _| count += 1));
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn earley_limit_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\na\nS->SS\nS->a\nS").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::with_limit(100);
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "aa"));
+        assert!(!Parser::predict(&mut parser, &"a".repeat(50)));
+        assert_eq!(
+            parser.parse(&"a".repeat(50)),
+            Err(ParseError::LimitExceeded)
+        );
+        assert_eq!(parser.predict_at(&"a".repeat(50)), Err(None));
+        assert!(parser.parse("aaa").is_ok());
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, &"a".repeat(50)));
     }
 }
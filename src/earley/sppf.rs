@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// Node of a shared packed parse forest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SppfNode {
+    /// Letter of the word at the position.
+    Leaf { letter: char, pos: usize },
+    /// Symbol deriving `word[start..end]`, one packed node per derivation.
+    Symbol {
+        symbol: char,
+        start: usize,
+        end: usize,
+        packed: Vec<usize>,
+    },
+    /// First `pos` symbols of the rule deriving `word[start..end]`.
+    Intermediate {
+        rule: CFRule,
+        pos: usize,
+        start: usize,
+        end: usize,
+        packed: Vec<usize>,
+    },
+    /// Derivation splitting a rule prefix into the shorter prefix and the last symbol.
+    Packed {
+        rule: CFRule,
+        left: Option<usize>,
+        right: Option<usize>,
+    },
+}
+
+/// Shared packed parse forest compactly representing all parse trees of a word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sppf {
+    nodes: Vec<SppfNode>,
+    root: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SppfKey {
+    Leaf(usize),
+    Symbol(char, usize, usize),
+    Intermediate(CFRule, usize, usize, usize),
+}
+
+impl Sppf {
+    pub fn nodes(&self) -> &[SppfNode] {
+        &self.nodes
+    }
+
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// Number of parse trees in the forest.
+    ///
+    /// Derivations repeating a node, possible only for cyclic grammars, are skipped.
+    /// The counting saturates, so there is no number if it reaches `usize::MAX`.
+    pub fn count(&self) -> Option<usize> {
+        Some(self.get_counts()[self.root]).filter(|count| *count != usize::MAX)
+    }
+
+    /// Lazily enumerate the parse trees of the forest.
+    pub fn trees(&self) -> impl Iterator<Item = ParseTree> + '_ {
+        let counts = self.get_counts();
+        (0..counts[self.root]).map(move |index| self.get_tree(&counts, self.root, index))
+    }
+
+    /// Most probable parse tree with its probability, given the rule probabilities.
+    ///
+    /// Derivations repeating a node, possible only for cyclic grammars, are skipped.
+    pub fn best_tree<F>(&self, weight: F) -> Option<(ParseTree, f64)>
+    where
+        F: Fn(&CFRule) -> f64,
+    {
+        let mut best = vec![None; self.nodes.len()];
+        let mut visiting = HashSet::new();
+        let score = self.score_node(self.root, &weight, &mut best, &mut visiting)?;
+        Some((self.get_best_tree(&best, self.root), score))
+    }
+
+    /// Best score of the node, remembering the best packed node of every choice.
+    fn score_node<F>(
+        &self,
+        node: usize,
+        weight: &F,
+        best: &mut Vec<Option<(f64, usize)>>,
+        visiting: &mut HashSet<usize>,
+    ) -> Option<f64>
+    where
+        F: Fn(&CFRule) -> f64,
+    {
+        if let Some((score, _)) = best[node] {
+            return Some(score);
+        }
+
+        if !visiting.insert(node) {
+            return None;
+        }
+
+        let score = match &self.nodes[node] {
+            SppfNode::Leaf { .. } => Some(1.0),
+            SppfNode::Symbol { packed, .. } | SppfNode::Intermediate { packed, .. } => {
+                let is_symbol = matches!(self.nodes[node], SppfNode::Symbol { .. });
+                let mut chosen = None;
+
+                for next in packed.iter() {
+                    let Some(mut score) = self.score_node(*next, weight, best, visiting) else {
+                        continue;
+                    };
+
+                    if let (true, SppfNode::Packed { rule, .. }) = (is_symbol, &self.nodes[*next]) {
+                        score *= weight(rule);
+                    }
+
+                    if chosen.is_none_or(|(best_score, _)| score > best_score) {
+                        chosen = Some((score, *next));
+                    }
+                }
+
+                best[node] = chosen;
+                chosen.map(|(score, _)| score)
+            }
+            SppfNode::Packed { left, right, .. } => {
+                let mut score = 1.0;
+
+                for next in [left, right].into_iter().flatten() {
+                    score *= self.score_node(*next, weight, best, visiting)?;
+                }
+
+                Some(score)
+            }
+        };
+
+        visiting.remove(&node);
+        score
+    }
+
+    fn get_best_tree(&self, best: &[Option<(f64, usize)>], node: usize) -> ParseTree {
+        match &self.nodes[node] {
+            SppfNode::Leaf { letter, .. } => ParseTree::Leaf(*letter),
+            _ => {
+                let packed = best[node].expect("The node has a best derivation.").1;
+                let SppfNode::Packed { rule, .. } = &self.nodes[packed] else {
+                    unreachable!("Symbol nodes only have packed children.");
+                };
+                ParseTree::Node {
+                    rule: rule.clone(),
+                    children: self.get_best_children(best, packed),
+                }
+            }
+        }
+    }
+
+    fn get_best_children(&self, best: &[Option<(f64, usize)>], packed: usize) -> Vec<ParseTree> {
+        let SppfNode::Packed { left, right, .. } = &self.nodes[packed] else {
+            unreachable!("Children are only built for packed nodes.");
+        };
+        let mut children = match left {
+            Some(left) => {
+                let packed = best[*left].expect("The node has a best derivation.").1;
+                self.get_best_children(best, packed)
+            }
+            None => Vec::new(),
+        };
+
+        if let Some(right) = right {
+            children.push(self.get_best_tree(best, *right));
+        }
+
+        children
+    }
+
+    fn get_counts(&self) -> Vec<usize> {
+        let mut counts = vec![None; self.nodes.len()];
+        let mut visiting = HashSet::new();
+        self.count_node(self.root, &mut counts, &mut visiting);
+        counts.into_iter().map(Option::unwrap_or_default).collect()
+    }
+
+    fn count_node(
+        &self,
+        node: usize,
+        counts: &mut Vec<Option<usize>>,
+        visiting: &mut HashSet<usize>,
+    ) -> usize {
+        if let Some(count) = counts[node] {
+            return count;
+        }
+
+        if !visiting.insert(node) {
+            return 0;
+        }
+
+        let count = match &self.nodes[node] {
+            SppfNode::Leaf { .. } => 1,
+            SppfNode::Symbol { packed, .. } | SppfNode::Intermediate { packed, .. } => {
+                packed.iter().fold(0, |count: usize, next| {
+                    count.saturating_add(self.count_node(*next, counts, visiting))
+                })
+            }
+            SppfNode::Packed { left, right, .. } => [left, right]
+                .into_iter()
+                .flatten()
+                .fold(1, |count: usize, next| {
+                    count.saturating_mul(self.count_node(*next, counts, visiting))
+                }),
+        };
+
+        visiting.remove(&node);
+        counts[node] = Some(count);
+        count
+    }
+
+    fn get_tree(&self, counts: &[usize], node: usize, index: usize) -> ParseTree {
+        match &self.nodes[node] {
+            SppfNode::Leaf { letter, .. } => ParseTree::Leaf(*letter),
+            SppfNode::Symbol { packed, .. } => {
+                let (packed, index) = Self::choose(counts, packed, index);
+                let SppfNode::Packed { rule, .. } = &self.nodes[packed] else {
+                    unreachable!("Symbol nodes only have packed children.");
+                };
+                ParseTree::Node {
+                    rule: rule.clone(),
+                    children: self.get_children(counts, packed, index),
+                }
+            }
+            _ => unreachable!("Trees are only built for symbol and leaf nodes."),
+        }
+    }
+
+    fn get_children(&self, counts: &[usize], packed: usize, index: usize) -> Vec<ParseTree> {
+        let SppfNode::Packed { left, right, .. } = &self.nodes[packed] else {
+            unreachable!("Children are only built for packed nodes.");
+        };
+        let right_count = right.map_or(1, |right| counts[right]);
+        let mut children = match left {
+            Some(left) => {
+                let SppfNode::Intermediate { packed, .. } = &self.nodes[*left] else {
+                    unreachable!("Left children are intermediate nodes.");
+                };
+                let (packed, index) = Self::choose(counts, packed, index / right_count);
+                self.get_children(counts, packed, index)
+            }
+            None => Vec::new(),
+        };
+
+        if let Some(right) = right {
+            children.push(self.get_tree(counts, *right, index % right_count));
+        }
+
+        children
+    }
+
+    fn choose(counts: &[usize], packed: &[usize], mut index: usize) -> (usize, usize) {
+        for next in packed.iter() {
+            if index < counts[*next] {
+                return (*next, index);
+            }
+
+            index -= counts[*next];
+        }
+
+        unreachable!("The tree index is out of range.")
+    }
+}
+
+impl EarleyParser {
+    /// Build the shared packed parse forest of all parse trees of the word.
+    pub fn parse_forest(&mut self, word: &str) -> Option<Sppf> {
+        let letters: Vec<_> = word.chars().collect();
+
+        if !self.fill_chart(&letters, false) {
+            return None;
+        }
+
+        let start = self.get_start_rule().1;
+        let mut builder = SppfBuilder {
+            parser: self,
+            letters: &letters,
+            nodes: Vec::new(),
+            mapping: HashMap::new(),
+        };
+        let symbol = start.chars().next().unwrap_or_default();
+        let root = builder.get_symbol(symbol, 0, letters.len());
+        Some(Sppf {
+            nodes: builder.nodes,
+            root,
+        })
+    }
+
+    /// Rules applied in at least one parse tree of the word, none if the word
+    /// is not in the language.
+    pub fn used_rules(&mut self, word: &str) -> HashSet<CFRule> {
+        let Some(forest) = self.parse_forest(word) else {
+            return HashSet::new();
+        };
+
+        // The forest is built from the root, so every node is in some tree.
+        forest
+            .nodes
+            .into_iter()
+            .filter_map(|node| match node {
+                SppfNode::Packed { rule, .. } => Some(rule),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+struct SppfBuilder<'a> {
+    parser: &'a EarleyParser,
+    letters: &'a [char],
+    nodes: Vec<SppfNode>,
+    mapping: HashMap<SppfKey, usize>,
+}
+
+impl SppfBuilder<'_> {
+    fn add_node(&mut self, key: SppfKey, node: SppfNode) -> (usize, bool) {
+        if let Some(id) = self.mapping.get(&key) {
+            return (*id, false);
+        }
+
+        self.nodes.push(node);
+        self.mapping.insert(key, self.nodes.len() - 1);
+        (self.nodes.len() - 1, true)
+    }
+
+    fn get_leaf(&mut self, pos: usize) -> usize {
+        let letter = self.letters[pos];
+        self.add_node(SppfKey::Leaf(pos), SppfNode::Leaf { letter, pos })
+            .0
+    }
+
+    fn get_symbol(&mut self, symbol: char, start: usize, end: usize) -> usize {
+        let (id, created) = self.add_node(
+            SppfKey::Symbol(symbol, start, end),
+            SppfNode::Symbol {
+                symbol,
+                start,
+                end,
+                packed: Vec::new(),
+            },
+        );
+
+        if created {
+            let mut rules: Vec<_> = self.parser.situations[end]
+                .iter()
+                .filter(|situation| {
+                    situation.rule.0 == symbol
+                        && situation.prev_cnt == start
+                        && situation.pos == situation.rule.1.chars().count()
+                })
+                .map(|situation| situation.rule.clone())
+                .collect();
+            rules.sort();
+            let mut packed = Vec::new();
+
+            for rule in rules {
+                let pos = rule.1.chars().count();
+                packed.extend(self.get_packed(&rule, pos, start, end));
+            }
+
+            if let SppfNode::Symbol { packed: node, .. } = &mut self.nodes[id] {
+                *node = packed;
+            }
+        }
+
+        id
+    }
+
+    fn get_intermediate(&mut self, rule: &CFRule, pos: usize, start: usize, end: usize) -> usize {
+        let (id, created) = self.add_node(
+            SppfKey::Intermediate(rule.clone(), pos, start, end),
+            SppfNode::Intermediate {
+                rule: rule.clone(),
+                pos,
+                start,
+                end,
+                packed: Vec::new(),
+            },
+        );
+
+        if created {
+            let packed = self.get_packed(rule, pos, start, end);
+
+            if let SppfNode::Intermediate { packed: node, .. } = &mut self.nodes[id] {
+                *node = packed;
+            }
+        }
+
+        id
+    }
+
+    /// Packed nodes for the ways the first `pos` symbols of the rule derive `word[start..end]`.
+    fn get_packed(&mut self, rule: &CFRule, pos: usize, start: usize, end: usize) -> Vec<usize> {
+        if pos == 0 {
+            let node = SppfNode::Packed {
+                rule: rule.clone(),
+                left: None,
+                right: None,
+            };
+            self.nodes.push(node);
+            return vec![self.nodes.len() - 1];
+        }
+
+        let symbol = rule.1.chars().nth(pos - 1).unwrap();
+        let prev_situation = EarleySituation::new(rule, pos - 1, start);
+        let mut splits = Vec::new();
+
+        if self.parser.nullable.contains(&symbol)
+            || self
+                .parser
+                .grammar
+                .as_ref()
+                .unwrap()
+                .is_non_terminal(&symbol)
+        {
+            let mut middles: Vec<_> = self.parser.situations[end]
+                .iter()
+                .filter(|situation| {
+                    situation.rule.0 == symbol
+                        && situation.pos == situation.rule.1.chars().count()
+                        && situation.prev_cnt >= start
+                })
+                .map(|situation| situation.prev_cnt)
+                .collect();
+            middles.sort();
+            middles.dedup();
+
+            for middle in middles {
+                if self.parser.situations[middle].contains(&prev_situation) {
+                    splits.push((middle, None));
+                }
+            }
+        } else if end > start
+            && (self.letters[end - 1] == symbol || symbol == ANY_TERMINAL)
+            && self.parser.situations[end - 1].contains(&prev_situation)
+        {
+            splits.push((end - 1, Some(end - 1)));
+        }
+
+        let mut packed = Vec::new();
+
+        for (middle, leaf) in splits {
+            if pos == 1 && middle != start {
+                continue;
+            }
+
+            let left = (pos > 1).then(|| self.get_intermediate(rule, pos - 1, start, middle));
+            let right = match leaf {
+                Some(leaf) => self.get_leaf(leaf),
+                None => self.get_symbol(symbol, middle, end),
+            };
+            self.nodes.push(SppfNode::Packed {
+                rule: rule.clone(),
+                left,
+                right: Some(right),
+            });
+            packed.push(self.nodes.len() - 1);
+        }
+
+        packed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sppf_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("E\n+a\nE->E+E\nE->a\nE").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let forest = parser.parse_forest("a+a+a").expect("Parse unsuccessful");
+        let trees: Vec<_> = forest.trees().collect();
+        assert_eq!(forest.count(), Some(2));
+        assert_eq!(trees.len(), 2);
+        assert_ne!(trees[0], trees[1]);
+        assert_eq!(forest.trees().take(1).count(), 1);
+        assert!(parser.parse_forest("a+").is_none());
+
+        // The Catalan number of the trees does not fit into 64 bits.
+        let forest = parser
+            .parse_forest(&("a+".repeat(40) + "a"))
+            .expect("Parse unsuccessful");
+        assert_eq!(forest.count(), None);
+        assert!(forest.trees().next().is_some());
+    }
+
+    #[test]
+    fn sppf_unit_test_2() {
+        let grammar = CFGrammar::from_str("SC\ncd\nS->CC\nC->cC\nC->d\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let forest = parser.parse_forest("cdd").expect("Parse unsuccessful");
+        let trees: Vec<_> = forest.trees().collect();
+        assert_eq!(trees, vec![parser.parse("cdd").unwrap()]);
+    }
+
+    #[test]
+    fn sppf_wildcard_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\na\nS->.S|a\nS").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let forest = parser.parse_forest("xya").expect("Parse unsuccessful");
+        let trees: Vec<_> = forest.trees().collect();
+        assert_eq!(trees, vec![parser.parse("xya").unwrap()]);
+    }
+
+    #[test]
+    fn used_rules_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("E\n+*a\nE->E+E|E*E|a\nE").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let rules = |rules: &[(char, &str)]| {
+            HashSet::from_iter(rules.iter().map(|rule| (rule.0, rule.1.to_string())))
+        };
+        assert_eq!(parser.used_rules("a+a"), rules(&[('E', "E+E"), ('E', "a")]));
+        assert_eq!(
+            parser.used_rules("a*a+a"),
+            rules(&[('E', "E+E"), ('E', "E*E"), ('E', "a")])
+        );
+        assert_eq!(parser.used_rules("a+"), HashSet::new());
+
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AS|\nA->a|\nB->b\nS")
+            .expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(
+            parser.used_rules(""),
+            rules(&[('S', "AS"), ('S', ""), ('A', "")])
+        );
+        assert_eq!(
+            parser.used_rules("a"),
+            rules(&[('S', "AS"), ('S', ""), ('A', "a"), ('A', "")])
+        );
+    }
+
+    #[test]
+    fn sppf_unit_test_3() {
+        let grammar = CFGrammar::from_str("SA\na\nS->AS\nS->\nA->a\nA->\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let forest = parser.parse_forest("a").expect("Parse unsuccessful");
+        assert!(forest.count().is_some_and(|count| count >= 1));
+        assert!(forest.trees().all(|tree| tree.symbol() == 'S'));
+    }
+}
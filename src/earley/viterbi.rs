@@ -0,0 +1,100 @@
+use super::*;
+
+/// Earley parser choosing the most probable derivation of a weighted grammar.
+#[derive(Debug, Default, Clone)]
+pub struct WeightedEarleyParser {
+    parser: EarleyParser,
+    grammar: Option<WeightedGrammar>,
+}
+
+impl WeightedEarleyParser {
+    pub fn new() -> Self {
+        Self {
+            parser: EarleyParser::new(),
+            grammar: None,
+        }
+    }
+
+    pub fn fit(&mut self, grammar: &WeightedGrammar) -> Result<(), anyhow::Error> {
+        grammar
+            .validate()
+            .with_context(|| "The rule weights are not probabilities.")?;
+        self.parser.fit(grammar.grammar())?;
+        self.grammar = Some(grammar.clone());
+        Ok(())
+    }
+
+    /// Most probable parse tree of the word with its probability.
+    pub fn viterbi_parse(&mut self, word: &str) -> Option<(ParseTree, f64)> {
+        let grammar = self.grammar.as_ref()?;
+        let forest = self.parser.parse_forest(word)?;
+        forest.best_tree(|rule| grammar.weight(rule))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn viterbi_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("E\n+a\nE->E+E\nE->a\nE").expect("Failed to parse the grammar.");
+        let weights = HashMap::from([
+            (('E', "E+E".to_string()), 0.5),
+            (('E', "a".to_string()), 0.5),
+        ]);
+        let mut parser = WeightedEarleyParser::new();
+        parser
+            .fit(&WeightedGrammar::new(&grammar, &weights))
+            .expect("Fit unsuccessful");
+        let (_, score) = parser.viterbi_parse("a+a+a").expect("Parse unsuccessful");
+        assert!((score - 0.5f64.powi(5)).abs() < 1e-12);
+        assert!(parser.viterbi_parse("a+").is_none());
+    }
+
+    #[test]
+    fn viterbi_unit_test_2() {
+        // The word "ab" is derived either as S -> AB or as S -> C.
+        let grammar = CFGrammar::from_str("SABC\nab\nS->AB\nS->C\nA->a\nB->b\nC->ab\nS")
+            .expect("Failed to parse the grammar.");
+        let mut weights = HashMap::from([
+            (('S', "AB".to_string()), 0.7),
+            (('S', "C".to_string()), 0.3),
+            (('A', "a".to_string()), 1.0),
+            (('B', "b".to_string()), 1.0),
+            (('C', "ab".to_string()), 1.0),
+        ]);
+        let mut parser = WeightedEarleyParser::new();
+        parser
+            .fit(&WeightedGrammar::new(&grammar, &weights))
+            .expect("Fit unsuccessful");
+        let (tree, score) = parser.viterbi_parse("ab").expect("Parse unsuccessful");
+        assert_eq!(tree.symbol(), 'S');
+        assert!(matches!(tree, ParseTree::Node { ref rule, .. } if rule.1 == "AB"));
+        assert!((score - 0.7).abs() < 1e-12);
+
+        weights.insert(('S', "AB".to_string()), 0.2);
+        weights.insert(('S', "C".to_string()), 0.8);
+        parser
+            .fit(&WeightedGrammar::new(&grammar, &weights))
+            .expect("Fit unsuccessful");
+        let (tree, score) = parser.viterbi_parse("ab").expect("Parse unsuccessful");
+        assert!(matches!(tree, ParseTree::Node { ref rule, .. } if rule.1 == "C"));
+        assert!((score - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn viterbi_should_fail_1() {
+        let grammar =
+            CFGrammar::from_str("E\n+a\nE->E+E\nE->a\nE").expect("Failed to parse the grammar.");
+        let weights = HashMap::from([(('E', "a".to_string()), 1.0)]);
+        let mut parser = WeightedEarleyParser::new();
+        assert!(parser
+            .fit(&WeightedGrammar::new(&grammar, &weights))
+            .is_err());
+        assert!(parser.viterbi_parse("a").is_none());
+    }
+}
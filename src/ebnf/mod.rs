@@ -0,0 +1,251 @@
+use super::transform::{get_fresh_symbol, get_grammar};
+use super::*;
+
+impl CFGrammar {
+    /// Parse rules like `A = B (C | D)* "x"?;` desugaring groups, repetitions and
+    /// optional parts into fresh non-terminals. Quoted letters are terminals, bare
+    /// letters are non-terminals, and the left part of the first rule is the start.
+    pub fn from_ebnf(s: &str) -> Result<CFGrammar, anyhow::Error> {
+        let mut parser = EbnfParser {
+            letters: s.chars().collect(),
+            pos: 0,
+            used: s.chars().collect(),
+            terminals: HashSet::new(),
+            non_terminals: HashSet::new(),
+            defined: HashSet::new(),
+            rules: Vec::new(),
+        };
+        let mut start = None;
+
+        while parser.skip_spaces().is_some() {
+            let rule_left = parser.parse_rule()?;
+            start.get_or_insert(rule_left);
+        }
+
+        let Some(start) = start else {
+            bail!("There must be at least one rule.");
+        };
+
+        if let Some(symbol) = parser.non_terminals.difference(&parser.defined).next() {
+            bail!("Non-terminal '{}' has no rules.", symbol);
+        }
+
+        if let Some(symbol) = parser.terminals.intersection(&parser.non_terminals).next() {
+            bail!("Symbol '{}' is both a terminal and a non-terminal.", symbol);
+        }
+
+        Ok(get_grammar(
+            &parser.terminals,
+            &parser.non_terminals,
+            parser.rules,
+            start,
+        ))
+    }
+}
+
+struct EbnfParser {
+    letters: Vec<char>,
+    pos: usize,
+    used: HashSet<char>,
+    terminals: HashSet<char>,
+    non_terminals: HashSet<char>,
+    defined: HashSet<char>,
+    rules: Vec<CFRule>,
+}
+
+impl EbnfParser {
+    fn skip_spaces(&mut self) -> Option<char> {
+        while self.letters.get(self.pos)?.is_whitespace() {
+            self.pos += 1;
+        }
+
+        self.letters.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, letter: char) -> Result<(), anyhow::Error> {
+        match self.skip_spaces() {
+            Some(found) if found == letter => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(found) => bail!(
+                "Expected '{}' at position {}, found '{}'.",
+                letter,
+                self.pos,
+                found
+            ),
+            None => bail!("Expected '{}', found the end of the input.", letter),
+        }
+    }
+
+    fn parse_rule(&mut self) -> Result<char, anyhow::Error> {
+        let rule_left = self.parse_non_terminal()?;
+        self.expect('=')?;
+        let alternatives = self.parse_alternatives()?;
+        self.expect(';')?;
+        self.defined.insert(rule_left);
+
+        for rule_right in alternatives {
+            self.rules.push((rule_left, rule_right));
+        }
+
+        Ok(rule_left)
+    }
+
+    fn parse_non_terminal(&mut self) -> Result<char, anyhow::Error> {
+        match self.skip_spaces() {
+            Some(letter) if !"=;|()*+?\"".contains(letter) => {
+                self.pos += 1;
+                self.non_terminals.insert(letter);
+                Ok(letter)
+            }
+            Some(found) => bail!(
+                "Expected a non-terminal at position {}, found '{}'.",
+                self.pos,
+                found
+            ),
+            None => bail!("Expected a non-terminal, found the end of the input."),
+        }
+    }
+
+    fn parse_alternatives(&mut self) -> Result<Vec<String>, anyhow::Error> {
+        let mut alternatives = vec![self.parse_sequence()?];
+
+        while self.skip_spaces() == Some('|') {
+            self.pos += 1;
+            alternatives.push(self.parse_sequence()?);
+        }
+
+        Ok(alternatives)
+    }
+
+    fn parse_sequence(&mut self) -> Result<String, anyhow::Error> {
+        let mut sequence = String::new();
+
+        while let Some(letter) = self.skip_spaces() {
+            if "=;|)*+?".contains(letter) {
+                break;
+            }
+
+            let item = self.parse_item()?;
+            sequence.push_str(&item);
+        }
+
+        Ok(sequence)
+    }
+
+    fn parse_item(&mut self) -> Result<String, anyhow::Error> {
+        let atom = self.parse_atom()?;
+
+        let Some(operator) = self.skip_spaces().filter(|letter| "*+?".contains(*letter)) else {
+            return Ok(atom);
+        };
+
+        self.pos += 1;
+        let symbol = self.add_fresh_non_terminal();
+
+        match operator {
+            '*' => {
+                self.rules.push((symbol, format!("{}{}", atom, symbol)));
+                self.rules.push((symbol, String::new()));
+            }
+            '+' => {
+                self.rules.push((symbol, format!("{}{}", atom, symbol)));
+                self.rules.push((symbol, atom));
+            }
+            _ => {
+                self.rules.push((symbol, atom));
+                self.rules.push((symbol, String::new()));
+            }
+        }
+
+        Ok(symbol.to_string())
+    }
+
+    fn parse_atom(&mut self) -> Result<String, anyhow::Error> {
+        match self.skip_spaces() {
+            Some('"') => {
+                self.pos += 1;
+                let mut atom = String::new();
+
+                loop {
+                    match self.letters.get(self.pos) {
+                        Some('"') => break,
+                        Some(letter) => {
+                            self.terminals.insert(*letter);
+                            atom.push(*letter);
+                            self.pos += 1;
+                        }
+                        None => bail!("Unterminated terminal string."),
+                    }
+                }
+
+                self.pos += 1;
+                Ok(atom)
+            }
+            Some('(') => {
+                self.pos += 1;
+                let alternatives = self.parse_alternatives()?;
+                self.expect(')')?;
+                let symbol = self.add_fresh_non_terminal();
+
+                for rule_right in alternatives {
+                    self.rules.push((symbol, rule_right));
+                }
+
+                Ok(symbol.to_string())
+            }
+            _ => Ok(self.parse_non_terminal()?.to_string()),
+        }
+    }
+
+    fn add_fresh_non_terminal(&mut self) -> char {
+        let symbol = get_fresh_symbol(&mut self.used);
+        self.non_terminals.insert(symbol);
+        self.defined.insert(symbol);
+        symbol
+    }
+}
+
+#[cfg(all(test, feature = "earley"))]
+mod tests {
+    use super::*;
+    use crate::earley::EarleyParser;
+
+    #[test]
+    fn ebnf_unit_test_1() {
+        let grammar =
+            CFGrammar::from_ebnf("S = \"a\" S? \"b\";").expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "ab"));
+        assert!(Parser::predict(&mut parser, "aaabbb"));
+        assert!(!Parser::predict(&mut parser, ""));
+        assert!(!Parser::predict(&mut parser, "aab"));
+    }
+
+    #[test]
+    fn ebnf_unit_test_2() {
+        let grammar = CFGrammar::from_ebnf(
+            "E = T (\"+\" T)*;
+             T = F (\"*\" F)*;
+             F = \"(\" E \")\" | \"x\"+;",
+        )
+        .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "x"));
+        assert!(Parser::predict(&mut parser, "xx+x*(x+xxx)"));
+        assert!(!Parser::predict(&mut parser, "x+"));
+        assert!(!Parser::predict(&mut parser, "()"));
+    }
+
+    #[test]
+    fn ebnf_should_fail_1() {
+        assert!(CFGrammar::from_ebnf("").is_err());
+        assert!(CFGrammar::from_ebnf("S = \"a\" A;").is_err());
+        assert!(CFGrammar::from_ebnf("S = (\"a\";").is_err());
+        assert!(CFGrammar::from_ebnf("S = \"a;").is_err());
+        assert!(CFGrammar::from_ebnf("S = \"S\";").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::Path;
+
+use anyhow::Context;
+
+use super::*;
+
+/// Parts of a grammar file split into sections by the keyword lines.
+#[derive(Debug, Default)]
+struct Sections {
+    non_terminals: HashSet<char>,
+    terminals: HashSet<char>,
+    rules: Vec<String>,
+    start: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    NonTerminals,
+    Terminals,
+    Rules,
+    Start,
+}
+
+impl CFGrammar {
+    /// Read a grammar split across files with sections started by the keyword
+    /// lines `%non-terminals`, `%terminals`, `%rules` and `%start`:
+    ///
+    /// ```text
+    /// %non-terminals
+    /// SA
+    /// %terminals
+    /// ab
+    /// %rules
+    /// S -> aA
+    /// A -> b
+    /// %start
+    /// S
+    /// ```
+    ///
+    /// The declarations and the rules of all files are merged, the start is
+    /// taken from the first file and no other file may have one. Blank lines and
+    /// lines starting with `#` are skipped, every line of the declaration
+    /// sections is read as a set of symbols on its own, ranges included.
+    pub fn from_files(paths: &[&Path]) -> Result<Self, anyhow::Error> {
+        let mut merged = Sections::default();
+
+        for (i, path) in paths.iter().enumerate() {
+            let input = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read {path:?}."))?;
+            let sections = get_sections(&input).with_context(|| format!("In {path:?}."))?;
+
+            match (i, sections.start) {
+                (0, None) => bail!("The primary file {path:?} has no start."),
+                (0, start) => merged.start = start,
+                (_, Some(_)) => bail!("Only the primary file may have a start, found in {path:?}."),
+                (_, None) => {}
+            }
+
+            merged.non_terminals.extend(sections.non_terminals);
+            merged.terminals.extend(sections.terminals);
+            merged.rules.extend(sections.rules);
+        }
+
+        let Some(start) = merged.start else {
+            bail!("There must be at least one grammar file.");
+        };
+        let mut lines = vec![
+            get_declaration(merged.non_terminals.iter()),
+            get_declaration(merged.terminals.iter()),
+        ];
+        lines.extend(merged.rules);
+        lines.push(start);
+        Self::from_str(&lines.join("\n"))
+    }
+}
+
+fn get_sections(input: &str) -> Result<Sections, anyhow::Error> {
+    let mut sections = Sections::default();
+    let mut section = None;
+
+    for line in input.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let keyword = match line.trim_end() {
+            "%non-terminals" => Some(Section::NonTerminals),
+            "%terminals" => Some(Section::Terminals),
+            "%rules" => Some(Section::Rules),
+            "%start" => Some(Section::Start),
+            _ => None,
+        };
+
+        if keyword.is_some() {
+            section = keyword;
+            continue;
+        }
+
+        match section {
+            None => bail!("The line {line:?} is outside of any section."),
+            Some(Section::NonTerminals) => sections.non_terminals.extend(get_declared(line)?),
+            Some(Section::Terminals) => sections.terminals.extend(get_declared(line)?),
+            Some(Section::Rules) => sections.rules.push(line.to_string()),
+            Some(Section::Start) => {
+                if sections.start.is_some() {
+                    bail!("The start must be a single line.");
+                }
+
+                sections.start = Some(line.to_string());
+            }
+        }
+    }
+
+    Ok(sections)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_unit_test_1() {
+        let dir = get_test_dir("files_unit_test_1");
+        let lexical = dir.join("lexical.grammar");
+        let expr = dir.join("expr.grammar");
+        let main = dir.join("main.grammar");
+        std::fs::write(&lexical, "# Shared symbols.\n%terminals\na+*\n()\n").unwrap();
+        std::fs::write(
+            &expr,
+            "%non-terminals\nTF\n%rules\nT -> T*F | F\nF -> (E) | a\n",
+        )
+        .unwrap();
+        std::fs::write(
+            &main,
+            "%non-terminals\nE\n%rules\nE -> E+T | T\n%start\nE\n",
+        )
+        .unwrap();
+
+        let grammar =
+            CFGrammar::from_files(&[&main, &lexical, &expr]).expect("Failed to read the grammar.");
+        let expected = CFGrammar::from_str("ETF\na+*()\nE->E+T|T\nT->T*F|F\nF->(E)|a\nE")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar, expected);
+
+        assert!(CFGrammar::from_files(&[&lexical, &main]).is_err());
+        assert!(CFGrammar::from_files(&[&main, &main]).is_err());
+        assert!(CFGrammar::from_files(&[&main]).is_err());
+        assert!(CFGrammar::from_files(&[]).is_err());
+        assert!(CFGrammar::from_files(&[&dir.join("missing.grammar")]).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn files_range_test_1() {
+        let dir = get_test_dir("files_range_test_1");
+        let lexical = dir.join("lexical.grammar");
+        let main = dir.join("main.grammar");
+        std::fs::write(&lexical, "%terminals\n-/\n0-2\n").unwrap();
+        std::fs::write(
+            &main,
+            "%non-terminals\nSN\n%terminals\n+*\na\n-c\n%rules\nS -> N+S | N-S | N\nN -> 0 | 1 | 2 | a | c\n%start\nS\n",
+        )
+        .unwrap();
+
+        let grammar =
+            CFGrammar::from_files(&[&main, &lexical]).expect("Failed to read the grammar.");
+        let terminals: HashSet<_> = grammar.terminals().collect();
+        assert_eq!(terminals, HashSet::from_iter("+*-/012ac".chars()));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn files_should_fail_1() {
+        assert!(get_sections("SA\n%terminals\nab").is_err());
+        assert!(get_sections("%start\nS\nA").is_err());
+        let sections = get_sections("%rules\n\n# S -> b\nS -> a\n%start\nS").unwrap();
+        assert_eq!(sections.rules, ["S -> a"]);
+        assert!(get_sections("%terminals\nc-a").is_err());
+        assert_eq!(sections.start.as_deref(), Some("S"));
+    }
+
+    fn get_test_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("langram-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+/// Schema of the grammar in JSON, the rules are a map from the left parts to
+/// the lists of the right parts.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct JsonGrammar {
+    #[serde(default)]
+    terminals: BTreeSet<char>,
+    #[serde(default)]
+    non_terminals: BTreeSet<char>,
+    #[serde(default)]
+    rules: BTreeMap<char, Vec<String>>,
+    start: char,
+}
+
+impl CFGrammar {
+    /// Parse a grammar of the form `{"terminals": ["c", "d"], "non_terminals":
+    /// ["S", "C"], "rules": {"S": ["CC"], "C": ["cC", "d"]}, "start": "S"}`.
+    pub fn from_json(s: &str) -> Result<CFGrammar, anyhow::Error> {
+        let schema: JsonGrammar =
+            serde_json::from_str(s).with_context(|| "Invalid JSON grammar.")?;
+        let mut builder = CFGrammarBuilder::new().start(schema.start);
+
+        for symbol in schema.terminals {
+            builder = builder.terminal(symbol);
+        }
+
+        for symbol in schema.non_terminals {
+            builder = builder.non_terminal(symbol);
+        }
+
+        for (rule_left, rules_right) in schema.rules {
+            for rule_right in rules_right {
+                builder = builder.rule(rule_left, &rule_right);
+            }
+        }
+
+        builder.build()
+    }
+
+    /// Grammar in the format read by `from_json`, without the injected sentinels.
+    pub fn to_json(&self) -> String {
+        let mut rules = BTreeMap::<char, Vec<String>>::new();
+
+        for (rule_left, rule_right) in self.get_user_rules() {
+            rules.entry(rule_left).or_default().push(rule_right);
+        }
+
+        let schema = JsonGrammar {
+            terminals: self.get_user_terminals().into_iter().collect(),
+            non_terminals: self.get_user_non_terminals().into_iter().collect(),
+            rules,
+            start: self.get_user_start(),
+        };
+        serde_json::to_string(&schema)
+            .expect("The map keys are characters, so the schema is valid JSON.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_unit_test_1() {
+        let grammar = CFGrammar::from_json(
+            r#"{
+                "terminals": ["c", "d"],
+                "non_terminals": ["S", "C"],
+                "rules": {"S": ["CC"], "C": ["cC", "d"]},
+                "start": "S"
+            }"#,
+        )
+        .expect("Failed to parse the grammar.");
+        let expected = CFGrammar::from_str("SC\ncd\nS->CC\nC->cC\nC->d\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.to_string(), expected.to_string());
+        assert_eq!(
+            grammar.to_json(),
+            r#"{"terminals":["c","d"],"non_terminals":["C","S"],"rules":{"C":["cC","d"],"S":["CC"]},"start":"S"}"#
+        );
+    }
+
+    #[test]
+    fn json_unit_test_2() {
+        let grammar =
+            CFGrammar::from_str("S\n\"\\\nS->\"S\\\nS->\nS").expect("Failed to parse the grammar.");
+        let loaded =
+            CFGrammar::from_json(&grammar.to_json()).expect("Failed to parse the grammar.");
+        assert_eq!(loaded.to_string(), grammar.to_string());
+        assert_eq!(loaded.to_json(), grammar.to_json());
+    }
+
+    #[test]
+    fn json_surrogate_test_1() {
+        let grammar = CFGrammar::from_json(
+            r#"{"terminals": ["\ud83d\ude00", "\u03b1"], "rules": {"S": ["\u03b1S\ud83d\ude00", ""]}, "start": "S"}"#,
+        )
+        .expect("Failed to parse the grammar.");
+        let expected =
+            CFGrammar::from_str("S\nα😀\nS->αS😀|\nS").expect("Failed to parse the grammar.");
+        assert_eq!(grammar, expected);
+
+        assert!(CFGrammar::from_json(r#"{"terminals": ["\ud83d"], "start": "S"}"#).is_err());
+        assert!(CFGrammar::from_json(r#"{"terminals": ["\ud83da"], "start": "S"}"#).is_err());
+        assert!(CFGrammar::from_json(r#"{"terminals": ["\ud83d\u0061"], "start": "S"}"#).is_err());
+        assert!(CFGrammar::from_json(r#"{"terminals": ["\ude00"], "start": "S"}"#).is_err());
+    }
+
+    #[test]
+    fn json_should_fail_1() {
+        assert!(CFGrammar::from_json("").is_err());
+        assert!(CFGrammar::from_json("[]").is_err());
+        assert!(CFGrammar::from_json(r#"{"rules": {"S": ["a"]}}"#).is_err());
+        assert!(CFGrammar::from_json(r#"{"start": "SS", "rules": {}}"#).is_err());
+        assert!(CFGrammar::from_json(r#"{"start": "S", "rules": {"S": "a"}}"#).is_err());
+        assert!(CFGrammar::from_json(r#"{"start": "S", "extra": []}"#).is_err());
+        assert!(CFGrammar::from_json(r#"{"start": "S"} {}"#).is_err());
+    }
+}
@@ -1,34 +1,165 @@
 pub use std::collections::HashSet;
 pub use std::str::FromStr;
 
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+
 pub use anyhow::bail;
 pub use multimap::MultiMap;
 
+mod analysis;
+mod bnf;
+mod builder;
+mod dfa;
+mod ebnf;
+mod files;
+#[cfg(feature = "serde")]
+mod json;
+mod regex;
+mod sample;
+mod tokens;
+mod transform;
+mod weighted;
+
+pub use builder::CFGrammarBuilder;
+pub use dfa::SimpleDfa;
+pub use sample::{Rng, XorShiftRng};
+pub use tokens::{NamedTree, Vocabulary};
+pub use weighted::WeightedGrammar;
+
+#[cfg(feature = "cross-check")]
+mod check;
+#[cfg(feature = "cross-check")]
+pub use check::{cross_check, Mismatch};
+
+#[cfg(feature = "cyk")]
+pub mod cyk;
+
 #[cfg(feature = "earley")]
 pub mod earley;
 
+#[cfg(feature = "ll1")]
+pub mod ll;
+
 #[cfg(feature = "lr1")]
 pub mod lr1;
 
 pub const START_RULE: char = '\u{1}';
 pub const END_TERMINAL: char = '\u{2}';
+/// Marker of nullable words in FIRST sets. It is not a terminal of any grammar,
+/// the empty word is derived with rules whose right part is empty.
 pub const EPS_TERMINAL: char = '\u{3}';
+/// Terminal matching any single letter of the word, written as `.` in `from_str`
+/// unless `.` is declared. In FIRST and FOLLOW sets it stands for every letter.
+pub const ANY_TERMINAL: char = '\u{4}';
+
+/// Grammar symbol.
+pub trait Symbol: Eq + Hash + Clone + Ord + Debug {
+    /// Sequence of symbols used as the right part of a rule.
+    type Word: Eq + Hash + Clone + Ord + Debug;
+
+    /// Sentinel for the injected start non-terminal.
+    fn start_rule() -> Self;
+
+    /// Sentinel for the end of input terminal.
+    fn end_terminal() -> Self;
+
+    /// Marker of the empty word, never inserted into grammars.
+    fn eps_terminal() -> Self;
+
+    /// Word consisting of a single symbol.
+    fn to_word(&self) -> Self::Word;
+}
+
+impl Symbol for char {
+    type Word = String;
+
+    fn start_rule() -> Self {
+        START_RULE
+    }
+
+    fn end_terminal() -> Self {
+        END_TERMINAL
+    }
+
+    fn eps_terminal() -> Self {
+        EPS_TERMINAL
+    }
+
+    fn to_word(&self) -> Self::Word {
+        self.to_string()
+    }
+}
+
+impl Symbol for String {
+    type Word = Vec<String>;
+
+    fn start_rule() -> Self {
+        START_RULE.to_string()
+    }
+
+    fn end_terminal() -> Self {
+        END_TERMINAL.to_string()
+    }
+
+    fn eps_terminal() -> Self {
+        EPS_TERMINAL.to_string()
+    }
+
+    fn to_word(&self) -> Self::Word {
+        vec![self.clone()]
+    }
+}
+
+pub type Rule<S> = (S, <S as Symbol>::Word);
 
-pub type CFRule = (char, String);
+pub type CFRule = Rule<char>;
 
 #[derive(Debug, Default, Clone)]
-pub struct CFGrammar {
+pub struct Grammar<S: Symbol> {
     /// Terminal symbols.
-    terminals: HashSet<char>,
+    terminals: HashSet<S>,
     /// Non-terminal symbols.
-    non_terminals: HashSet<char>,
+    non_terminals: HashSet<S>,
     /// List of rules.
-    rules: MultiMap<char, String>,
+    rules: MultiMap<S, S::Word>,
     /// Start non-terminal.
-    start: char,
+    start: S,
+    /// Names of the rules that have one, dropped by the transformations.
+    labels: HashMap<Rule<S>, String>,
+}
+
+pub type CFGrammar = Grammar<char>;
+
+/// Grammars are equal if they have the same symbols, rules and start, no matter
+/// the order the rules were added in.
+impl<S: Symbol> PartialEq for Grammar<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.terminals == other.terminals
+            && self.non_terminals == other.non_terminals
+            && self.start == other.start
+            && self.get_sorted_rules() == other.get_sorted_rules()
+            && self.labels == other.labels
+    }
+}
+
+impl<S: Symbol> Eq for Grammar<S> {}
+
+impl<S: Symbol> Hash for Grammar<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut terminals: Vec<_> = self.terminals.iter().collect();
+        let mut non_terminals: Vec<_> = self.non_terminals.iter().collect();
+        terminals.sort();
+        non_terminals.sort();
+        terminals.hash(state);
+        non_terminals.hash(state);
+        self.start.hash(state);
+        self.get_sorted_rules().hash(state);
+    }
 }
 
-fn check_lines(lines: &Vec<&str>) -> Result<(), anyhow::Error> {
+fn check_lines(lines: &[&str]) -> Result<(), anyhow::Error> {
     if lines.len() < 4 {
         bail!("Invalid input string format.");
     } else {
@@ -36,7 +167,7 @@ fn check_lines(lines: &Vec<&str>) -> Result<(), anyhow::Error> {
     }
 }
 
-fn check_parts(parts: &Vec<&str>) -> Result<(), anyhow::Error> {
+fn check_parts(parts: &[&str]) -> Result<(), anyhow::Error> {
     if parts.len() != 2 {
         bail!("There must be exactly one delimiter in the rule.");
     } else {
@@ -45,33 +176,169 @@ fn check_parts(parts: &Vec<&str>) -> Result<(), anyhow::Error> {
 }
 
 fn check_key(key: &str) -> Result<char, anyhow::Error> {
-    if key.len() != 1 {
+    if key.chars().count() != 1 {
         bail!("There must be exactly one non-terminal in the left part of the CF grammar.");
     } else {
         Ok(key.chars().next().unwrap())
     }
 }
 
-fn check_start(start: &str) -> Result<char, anyhow::Error> {
-    if start.len() != 1 {
-        bail!("There must be exactly one start rule.");
-    } else {
-        Ok(start.chars().next().unwrap())
+fn check_rhs(
+    rule_right: &str,
+    terminals: &HashSet<char>,
+    non_terminals: &HashSet<char>,
+) -> Result<(), anyhow::Error> {
+    for symbol in rule_right.chars() {
+        if !terminals.contains(&symbol) && !non_terminals.contains(&symbol) {
+            bail!("The symbol {symbol:?} in the right part {rule_right:?} is not declared.");
+        }
+    }
+
+    Ok(())
+}
+
+/// Split the right part on `|`, which is written as `\|` when it is a terminal.
+fn split_alternatives(rule_right: &str) -> Vec<String> {
+    let mut alternatives = vec![String::new()];
+    let mut letters = rule_right.chars().peekable();
+
+    while let Some(letter) = letters.next() {
+        match letter {
+            '\\' if letters.peek() == Some(&'|') => {
+                letters.next();
+                alternatives.last_mut().unwrap().push('|');
+            }
+            '|' => alternatives.push(String::new()),
+            _ => alternatives.last_mut().unwrap().push(letter),
+        }
+    }
+
+    alternatives
+        .iter()
+        .map(|alternative| alternative.trim().to_string())
+        .collect()
+}
+
+/// Split off the label written as `@name` after whitespace at the end of the
+/// right part, e.g. `T+N @add`. The name consists of letters, digits and `_`.
+fn split_label(rule_right: &str, labelled: bool) -> (String, Option<String>) {
+    if let Some((rest, label)) = rule_right.rsplit_once('@').filter(|_| labelled) {
+        if (rest.is_empty() || rest.ends_with(char::is_whitespace))
+            && !label.is_empty()
+            && label
+                .chars()
+                .all(|symbol| symbol.is_alphanumeric() || symbol == '_')
+        {
+            return (rest.trim_end().to_string(), Some(label.to_string()));
+        }
+    }
+
+    (rule_right.to_string(), None)
+}
+
+/// Symbols of a declaration line, where `x-y` stands for the inclusive range of
+/// symbols and `\-` for the symbol `-`. A `-` without a symbol on either side is
+/// the symbol itself.
+fn get_declared(line: &str) -> Result<HashSet<char>, anyhow::Error> {
+    let mut chars = line.chars().peekable();
+    // Symbols paired with whether they are an unescaped `-`.
+    let mut tokens = Vec::new();
+
+    while let Some(symbol) = chars.next() {
+        match (symbol, chars.peek()) {
+            ('\\', Some('-')) => tokens.push((chars.next().unwrap(), false)),
+            (symbol, _) => tokens.push((symbol, symbol == '-')),
+        }
+    }
+
+    let mut symbols = HashSet::new();
+    let mut i = 0;
+
+    while i < tokens.len() {
+        match tokens[i..] {
+            [(first, _), (_, true), (last, _), ..] => {
+                if first > last {
+                    bail!("The declared range {first}-{last} is empty, a literal '-' is written as \\-.");
+                }
+
+                symbols.extend(first..=last);
+                i += 3;
+            }
+            _ => {
+                symbols.insert(tokens[i].0);
+                i += 1;
+            }
+        }
     }
+
+    Ok(symbols)
+}
+
+/// Declaration line of the symbols in the order `get_declared` reads back.
+fn get_declaration<'a>(symbols: impl Iterator<Item = &'a char>) -> String {
+    let mut symbols: Vec<_> = symbols.collect();
+    symbols.sort();
+    String::from_iter(symbols).replace('-', "\\-")
+}
+
+/// Drop blank lines and lines starting with `#`. The two declaration lines
+/// are kept as they are, so they may be empty or start with `#` themselves,
+/// only comments before them and after them are removed.
+fn strip_comments(s: &str) -> Vec<&str> {
+    let is_comment = |line: &&str| line.trim().is_empty() || line.starts_with('#');
+    let mut lines = s.lines().skip_while(|line| line.starts_with('#'));
+    let mut stripped: Vec<_> = lines.by_ref().take(2).collect();
+    stripped.extend(lines.filter(|line| !is_comment(line)));
+    stripped
+}
+
+fn check_start(start: &str) -> Result<Vec<char>, anyhow::Error> {
+    let starts: Vec<_> = start.trim().chars().collect();
+
+    if starts.is_empty() {
+        bail!("There must be at least one start non-terminal.");
+    }
+
+    for (i, symbol) in starts.iter().enumerate() {
+        if starts[..i].contains(symbol) {
+            bail!("The start non-terminal {symbol:?} is listed twice.");
+        }
+    }
+
+    Ok(starts)
 }
 
 impl FromStr for CFGrammar {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<_> = s.lines().collect();
+        Self::from_str_with_delimiter(s, "->")
+    }
+}
+
+impl CFGrammar {
+    /// Parse a grammar in the `from_str` format with the parts of the rules
+    /// separated by the delimiter instead of `->`, e.g. `:` or `::=`.
+    pub fn from_str_with_delimiter(s: &str, delimiter: &str) -> Result<Self, anyhow::Error> {
+        if delimiter.trim().is_empty() {
+            bail!("The rule delimiter must not be blank.");
+        }
+
+        let lines = strip_comments(s);
         check_lines(&lines)?;
-        let non_terminals: HashSet<_> = lines[0].chars().collect();
-        let terminals: HashSet<_> = lines[1].chars().collect();
+        let non_terminals = get_declared(lines[0])?;
+        let mut terminals = get_declared(lines[1])?;
+        let wildcard = !terminals.contains(&'.') && !non_terminals.contains(&'.');
+        let labelled = !terminals.contains(&'@') && !non_terminals.contains(&'@');
         let mut rules = MultiMap::new();
+        let mut labels = HashMap::new();
 
-        for i in 2..(lines.len() - 1) {
-            let parts: Vec<_> = lines[i].split("->").map(|s: &str| s.trim()).collect();
+        if let Some(symbol) = terminals.intersection(&non_terminals).next() {
+            bail!("The symbol {symbol:?} can not be both a terminal and a non-terminal.");
+        }
+
+        for line in lines.iter().take(lines.len() - 1).skip(2) {
+            let parts: Vec<_> = line.split(delimiter).map(|s: &str| s.trim()).collect();
             check_parts(&parts)?;
             let key = check_key(parts[0])?;
 
@@ -81,50 +348,489 @@ impl FromStr for CFGrammar {
                 );
             }
 
-            let value = parts[1].to_string();
-            rules.insert(key, value);
+            for alternative in split_alternatives(parts[1]) {
+                let (mut rule_right, label) = split_label(&alternative, labelled);
+
+                if wildcard && rule_right.contains('.') {
+                    rule_right = rule_right.replace('.', &ANY_TERMINAL.to_string());
+                    terminals.insert(ANY_TERMINAL);
+                }
+
+                check_rhs(&rule_right, &terminals, &non_terminals)?;
+
+                if let Some(label) = label {
+                    let rule = (key, rule_right.clone());
+
+                    if labels.get(&rule).is_some_and(|other| *other != label) {
+                        bail!("The rule {key} -> {rule_right} has two labels.");
+                    }
+
+                    labels.insert(rule, label);
+                }
+
+                rules.insert(key, rule_right);
+            }
         }
 
-        let start = check_start(lines.last().unwrap())?;
-        Ok(Self::new(&terminals, &non_terminals, &rules, start))
+        let starts = check_start(lines.last().unwrap())?;
+        let mut grammar = Self::new_with_starts(&terminals, &non_terminals, &rules, &starts)?;
+        grammar.labels = labels;
+        Ok(grammar)
     }
-}
 
-impl CFGrammar {
-    pub fn new(
+    /// Grammar deriving the words of any of the start non-terminals.
+    ///
+    /// The injected start rule stays the only one: several start non-terminals
+    /// get a fresh non-terminal with a rule for each of them, which becomes the
+    /// start, so the parsers still accept by completing a single rule.
+    pub fn new_with_starts(
         terminals: &HashSet<char>,
         non_terminals: &HashSet<char>,
         rules: &MultiMap<char, String>,
-        start: char,
+        starts: &[char],
+    ) -> Result<Self, anyhow::Error> {
+        match starts {
+            [] => bail!("There must be at least one start non-terminal."),
+            [start] => Ok(Self::new(terminals, non_terminals, rules, *start)),
+            _ => {
+                if let Some(start) = starts.iter().find(|start| !non_terminals.contains(start)) {
+                    bail!("The start symbol {start:?} is not a non-terminal.");
+                }
+
+                let mut used: HashSet<_> = terminals.union(non_terminals).copied().collect();
+                used.extend([START_RULE, END_TERMINAL, EPS_TERMINAL]);
+                let start = transform::get_fresh_symbol(&mut used);
+                let mut non_terminals = non_terminals.clone();
+                let mut rules = rules.clone();
+                non_terminals.insert(start);
+
+                for symbol in starts {
+                    rules.insert(start, symbol.to_string());
+                }
+
+                Ok(Self::new(terminals, &non_terminals, &rules, start))
+            }
+        }
+    }
+}
+
+impl fmt::Display for CFGrammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let non_terminals = self
+            .non_terminals
+            .iter()
+            .filter(|symbol| **symbol != START_RULE);
+        let terminals = self
+            .terminals
+            .iter()
+            .filter(|symbol| **symbol != END_TERMINAL && **symbol != EPS_TERMINAL);
+        let mut rules: Vec<_> = self
+            .rules
+            .flat_iter()
+            .filter(|(rule_left, _)| **rule_left != START_RULE)
+            .collect();
+        rules.sort();
+
+        writeln!(f, "{}", get_declaration(non_terminals))?;
+        writeln!(f, "{}", get_declaration(terminals))?;
+
+        for (rule_left, rule_right) in rules {
+            write!(f, "{rule_left} -> {}", rule_right.replace('|', "\\|"))?;
+
+            match self.labels.get(&(*rule_left, rule_right.clone())) {
+                Some(label) => writeln!(f, " @{label}")?,
+                None => writeln!(f)?,
+            }
+        }
+
+        write!(
+            f,
+            "{}",
+            self.rules.get(&START_RULE).cloned().unwrap_or_default()
+        )
+    }
+}
+
+/// Rules of the grammar, without the injected start one.
+impl<'a> IntoIterator for &'a CFGrammar {
+    type Item = (char, &'a str);
+    type IntoIter = Box<dyn Iterator<Item = (char, &'a str)> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.rules())
+    }
+}
+
+/// Grammar of the rules, where the symbols with rules are the non-terminals,
+/// the others are terminals and the left part of the first rule is the start.
+/// Without rules the grammar is empty and has no start.
+impl FromIterator<CFRule> for CFGrammar {
+    fn from_iter<I: IntoIterator<Item = CFRule>>(iter: I) -> Self {
+        let mut builder = CFGrammarBuilder::new();
+        let mut has_start = false;
+
+        for (rule_left, rule_right) in iter {
+            if !has_start {
+                builder = builder.start(rule_left);
+                has_start = true;
+            }
+
+            builder = builder.rule(rule_left, &rule_right);
+        }
+
+        // Undeclared symbols can not clash, so only the missing start fails.
+        builder.build().unwrap_or_default()
+    }
+}
+
+impl<S: Symbol> Grammar<S> {
+    pub fn new(
+        terminals: &HashSet<S>,
+        non_terminals: &HashSet<S>,
+        rules: &MultiMap<S, S::Word>,
+        start: S,
     ) -> Self {
         let mut grammar = Self {
             terminals: terminals.clone(),
             non_terminals: non_terminals.clone(),
             rules: rules.clone(),
-            start: START_RULE,
+            start: S::start_rule(),
+            labels: HashMap::new(),
         };
-        grammar.terminals.insert(END_TERMINAL);
-        grammar.terminals.insert(EPS_TERMINAL);
-        grammar.non_terminals.insert(START_RULE);
-        grammar.rules.insert(START_RULE, start.to_string());
+        grammar.terminals.insert(S::end_terminal());
+        grammar.non_terminals.insert(S::start_rule());
+        grammar.rules.insert(S::start_rule(), start.to_word());
         grammar
     }
 
-    pub fn is_terminal(&self, symbol: char) -> bool {
-        self.terminals.contains(&symbol)
+    /// Name of the rule given in the grammar, if it has one.
+    pub fn label(&self, rule: &Rule<S>) -> Option<&str> {
+        self.labels.get(rule).map(String::as_str)
     }
 
-    pub fn is_non_terminal(&self, symbol: char) -> bool {
-        self.non_terminals.contains(&symbol)
+    pub fn is_terminal(&self, symbol: &S) -> bool {
+        self.terminals.contains(symbol)
     }
 
-    fn get_start_rule(&self) -> CFRule {
-        let rules = self
-            .rules
-            .get_vec(&self.start)
-            .expect("There are no start rules.");
-        assert!(rules.len() == 1, "There must be exactly one start rule.");
-        (self.start, rules.first().unwrap().clone())
+    pub fn is_non_terminal(&self, symbol: &S) -> bool {
+        self.non_terminals.contains(symbol)
+    }
+
+    /// Rules of every non-terminal in a canonical order.
+    fn get_sorted_rules(&self) -> BTreeMap<&S, Vec<&S::Word>> {
+        self.rules
+            .iter_all()
+            .map(|(rule_left, rules_right)| {
+                let mut rules_right: Vec<_> = rules_right.iter().collect();
+                rules_right.sort();
+                (rule_left, rules_right)
+            })
+            .collect()
+    }
+
+    #[cfg_attr(not(any(feature = "earley", feature = "lr1")), allow(dead_code))]
+    fn get_start_rule(&self) -> Result<Rule<S>, anyhow::Error> {
+        match self.rules.get_vec(&self.start).map(Vec::as_slice) {
+            Some([rule_right]) => Ok((self.start.clone(), rule_right.clone())),
+            Some(_) => bail!("There must be exactly one start rule."),
+            None => bail!("There are no start rules."),
+        }
+    }
+}
+
+/// Grammar literal expanding to a `CFGrammarBuilder` chain.
+///
+/// Symbols are char literals, so rustc rejects names longer than one character.
+/// Non-terminals are the symbols that have rules, the other symbols of the right
+/// parts are terminals. Optional `terminals` and `non_terminals` sections declare
+/// symbols explicitly.
+///
+/// Evaluates to `Result<CFGrammar, anyhow::Error>`, failing like
+/// `CFGrammarBuilder::build` and also on upper case terminals not declared in
+/// the `terminals` section, which are likely misspelled non-terminals.
+///
+/// ```
+/// use langram::{grammar, CFGrammar, FromStr};
+///
+/// let grammar = grammar! {
+///     start: 'S';
+///     'S' -> "CC";
+///     'C' -> "cC";
+///     'C' -> "d";
+/// }
+/// .unwrap();
+/// let parsed = CFGrammar::from_str("SC\ncd\nS->CC\nC->cC\nC->d\nS").unwrap();
+/// assert_eq!(grammar.to_string(), parsed.to_string());
+///
+/// assert!(grammar! { start: 'S'; 'S' -> "Cc"; 'C' -> "d"; }.is_ok());
+/// assert!(grammar! { start: 'S'; 'S' -> "cD"; 'C' -> "d"; }.is_err());
+/// ```
+///
+/// ```compile_fail
+/// use langram::grammar;
+///
+/// let grammar = grammar! {
+///     start: 'S';
+///     'SS' -> "a";
+/// };
+/// ```
+#[macro_export]
+macro_rules! grammar {
+    (
+        $(terminals: $terminals:literal;)?
+        $(non_terminals: $non_terminals:literal;)?
+        start: $start:literal;
+        $($rule_left:literal -> $rule_right:literal;)*
+    ) => {{
+        let builder = $crate::CFGrammarBuilder::new();
+        $(let builder = $terminals.chars().fold(builder, |builder, symbol| builder.terminal(symbol));)?
+        $(let builder = $non_terminals
+            .chars()
+            .fold(builder, |builder, symbol| builder.non_terminal(symbol));)?
+        let builder = builder.start($start);
+        $(let builder = builder.rule($rule_left, $rule_right);)*
+        builder
+            .build()
+            .and_then(|grammar| $crate::check_macro_terminals(grammar, concat!($($terminals)?)))
+    }};
+}
+
+#[doc(hidden)]
+pub fn check_macro_terminals(
+    grammar: CFGrammar,
+    declared: &str,
+) -> Result<CFGrammar, anyhow::Error> {
+    let undeclared = grammar
+        .get_user_terminals()
+        .into_iter()
+        .filter(|symbol| symbol.is_uppercase() && !declared.contains(*symbol))
+        .min();
+
+    if let Some(symbol) = undeclared {
+        bail!("The symbol {symbol} has no rules, upper case terminals must be declared.");
+    }
+
+    Ok(grammar)
+}
+
+/// Concrete syntax tree of a derivation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTree {
+    /// Terminal symbol.
+    Leaf(char),
+    /// Applied rule with the subtrees of its right part.
+    Node {
+        rule: CFRule,
+        children: Vec<ParseTree>,
+    },
+}
+
+impl ParseTree {
+    /// Symbol at the root of the tree.
+    pub fn symbol(&self) -> char {
+        match self {
+            ParseTree::Leaf(letter) => *letter,
+            ParseTree::Node { rule, .. } => rule.0,
+        }
+    }
+
+    /// Label the grammar gives to the rule at the root of the tree.
+    pub fn label<'a>(&self, grammar: &'a CFGrammar) -> Option<&'a str> {
+        match self {
+            ParseTree::Leaf(_) => None,
+            ParseTree::Node { rule, .. } => grammar.label(rule),
+        }
+    }
+}
+
+/// Which words are accepted when checking the membership.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchMode {
+    /// The word is in the language.
+    #[default]
+    Exact,
+    /// The word can be extended to a word in the language.
+    Prefix,
+}
+
+/// Reason for a word to be rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// The parser has not been fitted to a grammar.
+    NotFitted,
+    /// The letter at the position can not continue the word.
+    UnexpectedSymbol { pos: usize, found: char },
+    /// The word ended before it could be derived.
+    UnexpectedEnd,
+    /// Parsing took more steps than the parser is allowed to.
+    LimitExceeded,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::NotFitted => write!(f, "The parser is not fitted to a grammar."),
+            ParseError::UnexpectedSymbol { pos, found } => {
+                write!(f, "Unexpected symbol {found:?} at position {pos}.")
+            }
+            ParseError::UnexpectedEnd => write!(f, "Unexpected end of the word."),
+            ParseError::LimitExceeded => write!(f, "The step limit is exceeded."),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Line and column of the letter the word was rejected at, the end of the
+    /// word if it ended unexpectedly. The raw char index is kept in `pos`.
+    pub fn position(&self, word: &str) -> Option<Position> {
+        match self {
+            ParseError::UnexpectedSymbol { pos, .. } => Some(Position::at(word, *pos)),
+            ParseError::UnexpectedEnd => Some(Position::at(word, word.chars().count())),
+            ParseError::NotFitted | ParseError::LimitExceeded => None,
+        }
+    }
+}
+
+/// Line and column of a letter of a multi-line word, both counted from zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Position {
+    /// Position of the letter at the char index, counting the `'\n'` letters
+    /// before it.
+    pub fn at(word: &str, index: usize) -> Self {
+        word.chars()
+            .take(index)
+            .fold(Position::default(), |position, letter| match letter {
+                '\n' => Position {
+                    line: position.line + 1,
+                    col: 0,
+                },
+                _ => Position {
+                    col: position.col + 1,
+                    ..position
+                },
+            })
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+impl CFGrammar {
+    /// Declared terminals, without the sentinels.
+    pub fn terminals(&self) -> impl Iterator<Item = char> + '_ {
+        self.terminals
+            .iter()
+            .copied()
+            .filter(|symbol| *symbol != END_TERMINAL && *symbol != EPS_TERMINAL)
+    }
+
+    /// Declared non-terminals, without the injected start one.
+    pub fn non_terminals(&self) -> impl Iterator<Item = char> + '_ {
+        self.non_terminals
+            .iter()
+            .copied()
+            .filter(|symbol| *symbol != START_RULE)
+    }
+
+    /// Rules of the grammar, without the injected start one.
+    pub fn rules(&self) -> impl Iterator<Item = (char, &str)> {
+        self.rules
+            .flat_iter()
+            .filter(|(rule_left, _)| **rule_left != START_RULE)
+            .map(|(rule_left, rule_right)| (*rule_left, rule_right.as_str()))
+    }
+
+    /// Right parts of the rules of the non-terminal.
+    pub fn rules_for(&self, non_terminal: char) -> &[String] {
+        self.rules.get_vec(&non_terminal).map_or(&[], Vec::as_slice)
+    }
+
+    /// Start non-terminal given by the user.
+    pub fn start_symbol(&self) -> char {
+        self.get_user_start()
+    }
+
+    /// Same grammar with another non-terminal as the start.
+    pub fn with_start(&self, start: char) -> Result<CFGrammar, anyhow::Error> {
+        if start == START_RULE || !self.is_non_terminal(&start) {
+            bail!("The start {start:?} is not a non-terminal of the grammar.");
+        }
+
+        let mut grammar = self.clone();
+        grammar.rules.remove(&START_RULE);
+        grammar.rules.insert(START_RULE, start.to_string());
+        Ok(grammar)
+    }
+
+    /// Same grammar with the rule named by the label.
+    pub fn with_label(&self, rule: &CFRule, label: &str) -> Result<CFGrammar, anyhow::Error> {
+        if !self.rules_for(rule.0).contains(&rule.1) {
+            bail!("There is no rule {} -> {}.", rule.0, rule.1);
+        }
+
+        let mut grammar = self.clone();
+        grammar.labels.insert(rule.clone(), label.to_string());
+        Ok(grammar)
+    }
+
+    /// Check that every letter of the word is a terminal, which tells unknown
+    /// letters apart from words rejected by the grammar.
+    pub fn check_word(&self, word: &str) -> Result<(), ParseError> {
+        check_letters(
+            &self.get_user_terminals(),
+            &word.chars().collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// Unfitted parser of the algorithm with the name, one of `cyk`, `earley`, `ll1`,
+/// `lr1`, `lalr1`, `slr1` and `glr` as far as their features are enabled.
+pub fn make_parser(kind: &str) -> Result<Box<dyn Parser>, anyhow::Error> {
+    match kind {
+        #[cfg(feature = "cyk")]
+        "cyk" => Ok(Box::new(cyk::CYKParser::new())),
+        #[cfg(feature = "earley")]
+        "earley" => Ok(Box::new(earley::EarleyParser::new())),
+        #[cfg(feature = "ll1")]
+        "ll1" => Ok(Box::new(ll::LL1Parser::new())),
+        #[cfg(feature = "lr1")]
+        "lr1" => Ok(Box::new(lr1::LR1Parser::new())),
+        #[cfg(feature = "lr1")]
+        "lalr1" => Ok(Box::new(lr1::lalr::LALR1Parser::new())),
+        #[cfg(feature = "lr1")]
+        "slr1" => Ok(Box::new(lr1::slr::SLRParser::new())),
+        #[cfg(feature = "lr1")]
+        "glr" => Ok(Box::new(lr1::glr::GLRParser::new())),
+        _ => bail!("Unknown or disabled parser {kind:?}."),
+    }
+}
+
+/// Fail on the first letter that is not one of the terminals, any letter is
+/// allowed if `ANY_TERMINAL` is among them.
+pub(crate) fn check_letters(terminals: &HashSet<char>, letters: &[char]) -> Result<(), ParseError> {
+    if terminals.contains(&ANY_TERMINAL) {
+        return Ok(());
+    }
+
+    match letters
+        .iter()
+        .position(|letter| !terminals.contains(letter))
+    {
+        Some(pos) => Err(ParseError::UnexpectedSymbol {
+            pos,
+            found: letters[pos],
+        }),
+        None => Ok(()),
     }
 }
 
@@ -132,8 +838,35 @@ pub trait Parser {
     /// Grammar preprocessing.
     fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error>;
 
+    /// Build a parse tree of the word.
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError>;
+
     /// Check if the word is in the language.
-    fn predict(&mut self, word: &str) -> bool;
+    fn predict(&mut self, word: &str) -> bool {
+        self.parse(word).is_ok()
+    }
+
+    /// Forget the fitted grammar along with everything built for it and for the
+    /// parsed words, so the parser has to be fitted again. Settings such as the
+    /// step limit are kept.
+    fn reset(&mut self);
+}
+
+/// Grammar of the arithmetic expressions shared by the tests.
+#[cfg(test)]
+pub(crate) fn get_test_grammar() -> CFGrammar {
+    let terminals = HashSet::from(['a', '+', '*', '(', ')']);
+    let non_terminals = HashSet::from(['S', 'T', 'F', 'N']);
+    let mut rules = MultiMap::new();
+    rules.insert('S', "N".to_string());
+    rules.insert('N', "T+N".to_string());
+    rules.insert('N', "T".to_string());
+    rules.insert('T', "F*T".to_string());
+    rules.insert('T', "F".to_string());
+    rules.insert('F', "(N)".to_string());
+    rules.insert('F', "a".to_string());
+    let start = 'S';
+    CFGrammar::new(&terminals, &non_terminals, &rules, start)
 }
 
 #[cfg(test)]
@@ -146,54 +879,478 @@ mod tests {
         let parsed_grammar =
             CFGrammar::from_str("STFN\na+*()\nS->N\nN->T+N\nN->T\nT->F*T\nT->F\nF->(N)\nF->a\nS")
                 .unwrap();
-        assert_eq!(test_grammar.non_terminals, parsed_grammar.non_terminals);
-        assert_eq!(test_grammar.terminals, parsed_grammar.terminals);
-        assert_eq!(test_grammar.rules, parsed_grammar.rules);
-        assert_eq!(test_grammar.start, parsed_grammar.start);
+        assert_eq!(test_grammar, parsed_grammar);
+    }
+
+    #[test]
+    fn grammar_eq_test_1() {
+        let grammar = CFGrammar::from_str("SA\nab\nS->aA\nS->b\nA->a\nA->\nS")
+            .expect("Failed to parse the grammar.");
+        let reordered = CFGrammar::from_str("AS\nba\nA->\nS->b\nA->a\nS->aA\nS")
+            .expect("Failed to parse the grammar.");
+        let other = CFGrammar::from_str("SA\nab\nS->aA\nS->b\nA->a\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar, reordered);
+        assert_ne!(grammar, other);
+        assert_eq!(HashSet::from([grammar, reordered, other]).len(), 2);
     }
 
     #[test]
     fn grammar_should_fail_1() {
         let grammar = CFGrammar::from_str("S\nab\nS->a\nb->a\nS");
-        assert_eq!(grammar.is_err(), true);
+        assert!(grammar.is_err());
     }
 
     #[test]
     fn grammar_should_fail_2() {
         let grammar = CFGrammar::from_str("S\nab\nSS->a\nb->a\nS");
-        assert_eq!(grammar.is_err(), true);
+        assert!(grammar.is_err());
     }
 
     #[test]
     fn grammar_should_fail_3() {
         let grammar = CFGrammar::from_str("S\nab\nS");
-        assert_eq!(grammar.is_err(), true);
+        assert!(grammar.is_err());
     }
 
     #[test]
     fn grammar_should_fail_4() {
         let grammar = CFGrammar::from_str("S\nab\nS->a\nS->b\nSS");
-        assert_eq!(grammar.is_err(), true);
+        assert!(grammar.is_err());
     }
 
     #[test]
     fn grammar_should_fail_5() {
         let grammar = CFGrammar::from_str("ST\nab\nS->T->a\nS");
-        assert_eq!(grammar.is_err(), true);
+        assert!(grammar.is_err());
+    }
+
+    #[test]
+    fn grammar_should_fail_6() {
+        let grammar = CFGrammar::from_str("Sa\nab\nS->a\nS");
+        assert!(grammar.is_err());
+    }
+
+    #[test]
+    fn grammar_should_fail_7() {
+        let grammar = CFGrammar::from_str("S\nab\nS->aTb\nS");
+        assert!(grammar.is_err());
+    }
+
+    #[test]
+    fn grammar_should_fail_8() {
+        let grammar = CFGrammar::from_str("S\nab\nS->a b\nS");
+        assert!(grammar.is_err());
+    }
+
+    #[test]
+    fn grammar_delimiter_test_1() {
+        let expected = CFGrammar::from_str("SA\nab\nS->aA\nS->\nA->b\nS")
+            .expect("Failed to parse the grammar.");
+
+        for (s, delimiter) in [
+            ("SA\nab\nS ::= aA\nS ::=\nA ::= b\nS", "::="),
+            ("SA\nab\nS: aA\nS:\nA: b\nS", ":"),
+            ("SA\nab\nS->aA\nS->\nA->b\nS", "->"),
+        ] {
+            let grammar = CFGrammar::from_str_with_delimiter(s, delimiter)
+                .expect("Failed to parse the grammar.");
+            assert_eq!(grammar, expected);
+        }
     }
 
-    fn get_test_grammar() -> CFGrammar {
-        let terminals = HashSet::from(['a', '+', '*', '(', ')']);
-        let non_terminals = HashSet::from(['S', 'T', 'F', 'N']);
+    #[test]
+    fn grammar_delimiter_should_fail_1() {
+        assert!(CFGrammar::from_str_with_delimiter("S\na\nS->a\nS", "::=").is_err());
+        assert!(CFGrammar::from_str_with_delimiter("S\na\nS ::= a ::= a\nS", "::=").is_err());
+        assert!(CFGrammar::from_str_with_delimiter("S\na\nS a\nS", " ").is_err());
+    }
+
+    #[test]
+    fn grammar_alternatives_test_1() {
+        let grammar =
+            CFGrammar::from_str("STFN\na+*()\nS->N\nN->T+N | T\nT->F*T|T->F\nF->(N)|a\nS");
+        assert!(grammar.is_err());
+        let grammar = CFGrammar::from_str("STFN\na+*()\nS->N\nN->T+N | T\nT->F*T|F\nF->(N)|a\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.rules, get_test_grammar().rules);
+    }
+
+    #[test]
+    fn grammar_alternatives_test_2() {
+        let grammar = CFGrammar::from_str("S\na|\\\nS -> aS | \\|S | \\ |\nS")
+            .expect("Failed to parse the grammar.");
+        let mut rules = grammar.rules.get_vec(&'S').cloned().unwrap_or_default();
+        rules.sort();
+        assert_eq!(rules, vec!["", "\\", "aS", "|S"]);
+        let printed =
+            CFGrammar::from_str(&grammar.to_string()).expect("Failed to parse the grammar.");
+        assert_eq!(printed, grammar);
+    }
+
+    #[test]
+    fn grammar_starts_test_1() {
+        let grammar = CFGrammar::from_str("PE\na;+\nP->E;P\nP->E;\nE->a+E|a\nPE")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.rules_for(START_RULE).len(), 1);
+        let start = grammar.get_user_start();
+        assert!(!['P', 'E'].contains(&start));
+        let mut starts = grammar.rules_for(start).to_vec();
+        starts.sort();
+        assert_eq!(starts, vec!["E", "P"]);
+        assert_eq!(grammar.words_up_to(3), vec!["a", "a+a", "a;"]);
+        let printed =
+            CFGrammar::from_str(&grammar.to_string()).expect("Failed to parse the grammar.");
+        assert_eq!(printed, grammar);
+    }
+
+    #[test]
+    fn grammar_starts_should_fail_1() {
+        assert!(CFGrammar::from_str("S\na\nS->a\nST").is_err());
+        assert!(CFGrammar::from_str("S\na\nS->a\n ").is_err());
+        let grammar = CFGrammar::new_with_starts(
+            &HashSet::from(['a']),
+            &HashSet::from(['S']),
+            &MultiMap::new(),
+            &[],
+        );
+        assert!(grammar.is_err());
+    }
+
+    #[test]
+    fn grammar_comments_test_1() {
+        let grammar = CFGrammar::from_str(
+            "# Balanced brackets\n\
+             S\n\
+             ()\n\
+             # Concatenation\n\
+             S->SS\n\
+             \n\
+             # Nesting\n\
+             S->(S)\n\
+             S->\n\
+             # Start\n\
+             S\n\
+             \n",
+        )
+        .expect("Failed to parse the grammar.");
+        let expected = CFGrammar::from_str("S\n()\nS->SS\nS->(S)\nS->\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn grammar_comments_test_2() {
+        let grammar = CFGrammar::from_str("S\n\n# No terminals\nS->\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.get_user_rules().len(), 1);
+        let grammar = CFGrammar::from_str("# Header\n# comments\nS\na\nS->a\nS");
+        assert!(grammar.is_ok());
+        // Comments only take whole lines.
+        assert!(CFGrammar::from_str("S\na\nS->a # a comment\nS").is_err());
+        assert!(CFGrammar::from_str("S\na\n# S->a\n\n").is_err());
+    }
+
+    #[test]
+    fn accessors_test_1() {
+        let grammar = get_test_grammar();
+        let mut terminals: Vec<_> = grammar.terminals().collect();
+        terminals.sort();
+        assert_eq!(terminals, vec!['(', ')', '*', '+', 'a']);
+        let mut non_terminals: Vec<_> = grammar.non_terminals().collect();
+        non_terminals.sort();
+        assert_eq!(non_terminals, vec!['F', 'N', 'S', 'T']);
+        assert_eq!(grammar.rules().count(), 7);
+        assert!(grammar.rules().any(|rule| rule == ('F', "(N)")));
+        assert!(grammar.rules().all(|rule| rule.0 != START_RULE));
+        assert_eq!(grammar.rules_for('S'), ["N"]);
+        assert!(grammar.rules_for('a').is_empty());
+    }
+
+    #[test]
+    fn start_test_1() {
+        let grammar = get_test_grammar();
+        assert_eq!(grammar.start_symbol(), 'S');
+        let other = grammar
+            .with_start('T')
+            .expect("Failed to change the start.");
+        assert_eq!(other.start_symbol(), 'T');
+        assert_eq!(other.rules_for(START_RULE), ["T"]);
+        assert_eq!(other.rules().count(), grammar.rules().count());
+        assert_eq!(other.with_start('S').unwrap(), grammar);
+        assert!(grammar.with_start('a').is_err());
+        assert!(grammar.with_start('X').is_err());
+        assert!(grammar.with_start(START_RULE).is_err());
+    }
+
+    #[test]
+    fn iter_test_1() {
+        let grammar = get_test_grammar();
+        let mut rules: Vec<_> = Vec::new();
+
+        for (rule_left, rule_right) in &grammar {
+            rules.push((rule_left, rule_right.to_string()));
+        }
+
+        assert_eq!(rules.len(), grammar.rules().count());
+        assert!(rules.iter().all(|rule| rule.0 != START_RULE));
+        rules.sort_by_key(|rule| rule.0 != 'S');
+        let collected: CFGrammar = rules.iter().cloned().collect();
+        assert_eq!(collected, grammar);
+        let collected: CFGrammar = grammar
+            .into_iter()
+            .filter(|rule| rule.0 == 'F')
+            .map(|(rule_left, rule_right)| (rule_left, rule_right.to_string()))
+            .collect();
+        assert_eq!(collected.start_symbol(), 'F');
+        assert!(collected.is_terminal(&'N'));
+        assert_eq!(CFGrammar::from_iter(Vec::new()), CFGrammar::default());
+    }
+
+    #[test]
+    fn label_test_1() {
+        let grammar = CFGrammar::from_str("NT\na+\nN -> T+N @add | T  @single\nT -> a\nN")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.label(&('N', "T+N".to_string())), Some("add"));
+        assert_eq!(grammar.label(&('N', "T".to_string())), Some("single"));
+        assert_eq!(grammar.label(&('T', "a".to_string())), None);
+        assert_eq!(grammar.rules_for('N'), ["T+N", "T"]);
+        let printed = CFGrammar::from_str(&grammar.to_string()).unwrap();
+        assert_eq!(printed, grammar);
+        assert_ne!(
+            printed,
+            printed.with_label(&('T', "a".to_string()), "a").unwrap()
+        );
+        assert!(grammar.with_label(&('T', "b".to_string()), "b").is_err());
+
+        let tree = ParseTree::Node {
+            rule: ('N', "T".to_string()),
+            children: vec![],
+        };
+        assert_eq!(tree.label(&grammar), Some("single"));
+        assert_eq!(ParseTree::Leaf('a').label(&grammar), None);
+
+        // Only a trailing name after whitespace is a label, unless '@' is a terminal.
+        let grammar =
+            CFGrammar::from_str("S\na@ \nS->a @a\nS->a@\nS").expect("Failed to parse the grammar.");
+        assert_eq!(grammar.rules_for('S'), ["a @a", "a@"]);
+        assert!(CFGrammar::from_str("S\na\nS->a@x\nS").is_err());
+        assert!(CFGrammar::from_str("S\na\nS->a @x|a @y\nS").is_err());
+    }
+
+    #[test]
+    #[cfg(all(feature = "cyk", feature = "earley", feature = "lr1"))]
+    fn make_parser_test_1() {
+        let grammar = get_test_grammar();
+
+        for kind in ["earley", "lr1", "lalr1", "slr1", "glr", "cyk"] {
+            let mut parser = make_parser(kind).expect("Unknown parser");
+            let grammar = match kind {
+                "cyk" => grammar.to_cnf(),
+                _ => grammar.clone(),
+            };
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            assert!(parser.predict("(a+a)*a"), "{kind}");
+            assert!(!parser.predict("(a+a"), "{kind}");
+        }
+
+        let mut parser = make_parser("ll1").expect("Unknown parser");
+        assert!(parser.fit(&grammar).is_err());
+        assert!(make_parser("LR1").is_err());
+        assert!(make_parser("").is_err());
+    }
+
+    #[test]
+    fn grammar_range_test_1() {
+        let grammar = CFGrammar::from_str("SND\n0-9\nS->N\nN->DN|D\nD->0|1|2|3|4|5|6|7|8|9\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.terminals().count(), 10);
+        assert_eq!(grammar.check_word("2024"), Ok(()));
+        assert!(grammar.check_word("20a").is_err());
+
+        #[cfg(feature = "earley")]
+        {
+            let mut parser = earley::EarleyParser::new();
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            assert!(Parser::predict(&mut parser, "2024"));
+            assert!(!Parser::predict(&mut parser, ""));
+        }
+
+        let grammar = CFGrammar::from_str("A-C\na\\-c-\nA->a-c\nB->c\nC->-\nA")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(
+            grammar.terminals().collect::<HashSet<_>>(),
+            HashSet::from(['a', 'c', '-'])
+        );
+        assert!(grammar.is_non_terminal(&'B'));
+        assert!(grammar.to_string().starts_with("ABC\n\\-ac\n"));
+        assert_eq!(CFGrammar::from_str(&grammar.to_string()).unwrap(), grammar);
+
+        assert!(CFGrammar::from_str("S\n9-0\nS->0\nS").is_err());
+        assert!(CFGrammar::from_str("S\n+-*\nS->+\nS").is_err());
+    }
+
+    #[test]
+    fn grammar_range_should_fail_1() {
+        // Declarations written before the ranges, with an unescaped '-'.
+        let err =
+            CFGrammar::from_str("ETF\na+-*()\nE->E+T|E-T|T\nT->T*F|F\nF->(E)|a\nE").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The declared range +-* is empty, a literal '-' is written as \\-."
+        );
+
+        let grammar = CFGrammar::from_str("ETF\na+\\-*()\nE->E+T|E-T|T\nT->T*F|F\nF->(E)|a\nE")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.is_terminal(&'-'));
+    }
+
+    #[test]
+    fn position_test_1() {
+        let word = "ab\ncd\n\ne";
+        assert_eq!(Position::at(word, 0), Position { line: 0, col: 0 });
+        assert_eq!(Position::at(word, 4), Position { line: 1, col: 1 });
+        assert_eq!(Position::at(word, 6), Position { line: 2, col: 0 });
+        assert_eq!(Position::at(word, 100), Position { line: 3, col: 1 });
+        assert_eq!(Position::at(word, 4).to_string(), "1:1");
+
+        let err = ParseError::UnexpectedSymbol { pos: 4, found: 'd' };
+        assert_eq!(err.position(word), Some(Position { line: 1, col: 1 }));
+        assert_eq!(
+            ParseError::UnexpectedEnd.position("ab\n"),
+            Some(Position { line: 1, col: 0 })
+        );
+        assert_eq!(ParseError::NotFitted.position(word), None);
+    }
+
+    #[test]
+    fn check_word_test_1() {
+        let grammar = get_test_grammar();
+        assert_eq!(grammar.check_word("a+(a)"), Ok(()));
+        assert_eq!(grammar.check_word(""), Ok(()));
+        assert_eq!(
+            grammar.check_word("a+b"),
+            Err(ParseError::UnexpectedSymbol { pos: 2, found: 'b' })
+        );
+        assert_eq!(
+            grammar.check_word("S"),
+            Err(ParseError::UnexpectedSymbol { pos: 0, found: 'S' })
+        );
+    }
+
+    #[test]
+    fn grammar_display_test_1() {
+        let grammar = get_test_grammar();
+        assert_eq!(
+            grammar.to_string(),
+            "FNST\n()*+a\nF -> (N)\nF -> a\nN -> T\nN -> T+N\nS -> N\nT -> F\nT -> F*T\nS"
+        );
+    }
+
+    #[test]
+    fn grammar_display_test_2() {
+        let sources = [
+            "STFN\na+*()\nS->N\nN->T+N\nN->T\nT->F*T\nT->F\nF->(N)\nF->a\nS",
+            "S\nab\nS->aSb\nS->\nS",
+            "SC\ncd\nS->CC\nC->cC\nC->d\nS",
+            "SAB\nab\nS->AB\nA->a\nA->\nB->b\nB->\nA",
+        ];
+
+        for source in sources {
+            let grammar = CFGrammar::from_str(source).unwrap();
+            let printed = CFGrammar::from_str(&grammar.to_string()).unwrap();
+            assert_eq!(grammar.non_terminals, printed.non_terminals);
+            assert_eq!(grammar.terminals, printed.terminals);
+            assert_eq!(grammar.start, printed.start);
+            assert_eq!(grammar.to_string(), printed.to_string());
+
+            for (rule_left, rule_right) in grammar.rules.iter_all() {
+                let mut expected = rule_right.clone();
+                let mut found = printed.rules.get_vec(rule_left).unwrap().clone();
+                expected.sort();
+                found.sort();
+                assert_eq!(expected, found);
+            }
+        }
+    }
+
+    #[test]
+    fn grammar_macro_test_1() {
+        let test_grammar = get_test_grammar();
+        let macro_grammar = grammar! {
+            start: 'S';
+            'S' -> "N";
+            'N' -> "T+N";
+            'N' -> "T";
+            'T' -> "F*T";
+            'T' -> "F";
+            'F' -> "(N)";
+            'F' -> "a";
+        }
+        .unwrap();
+        assert_eq!(test_grammar.non_terminals, macro_grammar.non_terminals);
+        assert_eq!(test_grammar.terminals, macro_grammar.terminals);
+        assert_eq!(test_grammar.rules, macro_grammar.rules);
+        assert_eq!(test_grammar.start, macro_grammar.start);
+    }
+
+    #[test]
+    fn grammar_macro_test_2() {
+        let parsed_grammar = CFGrammar::from_str("SA\nab\nS->aSb\nS->\nA->a\nS").unwrap();
+        let macro_grammar = grammar! {
+            terminals: "b";
+            non_terminals: "A";
+            start: 'S';
+            'S' -> "aSb";
+            'S' -> "";
+            'A' -> "a";
+        }
+        .unwrap();
+        assert_eq!(parsed_grammar.non_terminals, macro_grammar.non_terminals);
+        assert_eq!(parsed_grammar.terminals, macro_grammar.terminals);
+        assert_eq!(parsed_grammar.rules, macro_grammar.rules);
+    }
+
+    #[test]
+    fn grammar_macro_should_fail_1() {
+        let err = grammar! {
+            start: 'S';
+            'S' -> "aT";
+            'F' -> "a";
+        }
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The symbol T has no rules, upper case terminals must be declared."
+        );
+
+        let grammar = grammar! {
+            terminals: "T";
+            start: 'S';
+            'S' -> "aT";
+        }
+        .expect("Failed to build the grammar.");
+        assert!(grammar.is_terminal(&'T'));
+        assert!(grammar! { start: 'S'; }.is_ok());
+    }
+
+    #[test]
+    fn grammar_generic_test_1() {
+        let terminals = HashSet::from(["IDENT".to_string(), "+".to_string()]);
+        let non_terminals = HashSet::from(["expr".to_string()]);
         let mut rules = MultiMap::new();
-        rules.insert('S', "N".to_string());
-        rules.insert('N', "T+N".to_string());
-        rules.insert('N', "T".to_string());
-        rules.insert('T', "F*T".to_string());
-        rules.insert('T', "F".to_string());
-        rules.insert('F', "(N)".to_string());
-        rules.insert('F', "a".to_string());
-        let start = 'S';
-        CFGrammar::new(&terminals, &non_terminals, &rules, start)
+        rules.insert(
+            "expr".to_string(),
+            vec!["expr".to_string(), "+".to_string(), "IDENT".to_string()],
+        );
+        rules.insert("expr".to_string(), vec!["IDENT".to_string()]);
+        let grammar = Grammar::new(&terminals, &non_terminals, &rules, "expr".to_string());
+        assert!(grammar.is_terminal(&"IDENT".to_string()));
+        assert!(grammar.is_non_terminal(&"expr".to_string()));
+        assert!(!grammar.is_terminal(&"expr".to_string()));
+        assert_eq!(
+            grammar.get_start_rule().expect("No start rule."),
+            (String::start_rule(), vec!["expr".to_string()])
+        );
     }
 }
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use anyhow::Context;
+
+use super::*;
+
+/// Two rules competing for the same cell of the predictive table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub symbol: char,
+    pub letter: char,
+    /// The rule already in the cell and the one added to it.
+    pub rules: (CFRule, CFRule),
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Conflict for {:?} on {:?} between {} -> {} and {} -> {}.",
+            self.symbol,
+            self.letter,
+            self.rules.0 .0,
+            self.rules.0 .1,
+            self.rules.1 .0,
+            self.rules.1 .1
+        )
+    }
+}
+
+impl std::error::Error for Conflict {}
+
+/// Rule to expand the non-terminal with by the lookahead.
+type LL1Table = HashMap<char, HashMap<char, CFRule>>;
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LL1Parser {
+    table: LL1Table,
+    start: char,
+    /// Terminals of the fitted grammar, the only letters a word may consist of.
+    terminals: HashSet<char>,
+    /// Maximal number of moves of the stack machine per word.
+    limit: Option<usize>,
+}
+
+impl Parser for LL1Parser {
+    fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        self.reset();
+        let start_rule = grammar.get_start_rule()?;
+        let table =
+            Self::build_table(grammar).with_context(|| "The given grammar is not LL(1).")?;
+        self.table = table;
+        self.start = start_rule.1.chars().next().unwrap_or_default();
+        self.terminals = grammar.get_user_terminals();
+        Ok(())
+    }
+
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError> {
+        self.parse_tokens(&word.chars().collect::<Vec<_>>())
+    }
+
+    fn reset(&mut self) {
+        *self = LL1Parser {
+            limit: self.limit,
+            ..LL1Parser::new()
+        };
+    }
+}
+
+impl LL1Parser {
+    pub fn new() -> Self {
+        Self {
+            table: HashMap::new(),
+            start: char::default(),
+            terminals: HashSet::new(),
+            limit: None,
+        }
+    }
+
+    /// Parser giving up on words that take more than `limit` moves.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Rule the non-terminal is expanded with on the lookahead, if any.
+    pub fn get_rule(&self, symbol: char, letter: char) -> Option<&CFRule> {
+        let row = self.table.get(&symbol)?;
+
+        // The wildcard rule is taken on the letters without their own.
+        match row.get(&letter) {
+            None if letter != END_TERMINAL => row.get(&ANY_TERMINAL),
+            rule => rule,
+        }
+    }
+
+    /// Check if the already tokenized word is in the language.
+    pub fn predict_tokens(&self, tokens: &[char]) -> bool {
+        self.parse_tokens(tokens).is_ok()
+    }
+
+    /// Build the parse tree of the already tokenized word.
+    pub fn parse_tokens(&self, letters: &[char]) -> Result<ParseTree, ParseError> {
+        if self.table.is_empty() {
+            return Err(ParseError::NotFitted);
+        }
+
+        check_letters(&self.terminals, letters)?;
+        let mut stack = vec![END_TERMINAL, self.start];
+        // Expanded rules with the subtrees of their right parts built so far.
+        let mut nodes = Vec::<(CFRule, Vec<ParseTree>)>::new();
+        let mut tree = None;
+        let mut pos = 0;
+        let mut steps = 0;
+
+        while let Some(symbol) = stack.pop() {
+            steps += 1;
+
+            if self.limit.is_some_and(|limit| steps > limit) {
+                return Err(ParseError::LimitExceeded);
+            }
+
+            let letter = letters.get(pos).copied().unwrap_or(END_TERMINAL);
+
+            if symbol == END_TERMINAL {
+                if letter == END_TERMINAL {
+                    return tree.ok_or(ParseError::UnexpectedEnd);
+                }
+
+                break;
+            }
+
+            if !self.table.contains_key(&symbol) {
+                if symbol != letter && (symbol != ANY_TERMINAL || letter == END_TERMINAL) {
+                    break;
+                }
+
+                pos += 1;
+                nodes.last_mut().unwrap().1.push(ParseTree::Leaf(letter));
+            } else {
+                let Some(rule) = self.get_rule(symbol, letter) else {
+                    break;
+                };
+
+                stack.extend(rule.1.chars().rev());
+                nodes.push((rule.clone(), Vec::new()));
+            }
+
+            if let Some(root) = complete(&mut nodes) {
+                tree = Some(root);
+            }
+        }
+
+        match letters.get(pos) {
+            Some(found) => Err(ParseError::UnexpectedSymbol { pos, found: *found }),
+            None => Err(ParseError::UnexpectedEnd),
+        }
+    }
+
+    /// Predictive table of the grammar, built apart from the parser so a
+    /// conflict leaves it unfitted.
+    fn build_table(grammar: &CFGrammar) -> Result<LL1Table, Conflict> {
+        let first_sets = grammar.get_first_sets();
+        let follow_sets = grammar.get_follow_sets();
+        let mut table = LL1Table::new();
+
+        for (rule_left, rule_right) in grammar.get_user_rules() {
+            let mut letters = CFGrammar::first_with(&first_sets, &rule_right);
+
+            if letters.remove(&EPS_TERMINAL) {
+                letters.extend(follow_sets.get(&rule_left).into_iter().flatten());
+            }
+
+            for letter in letters {
+                add_rule(&mut table, letter, &(rule_left, rule_right.clone()))?;
+            }
+        }
+
+        Ok(table)
+    }
+}
+
+fn add_rule(table: &mut LL1Table, letter: char, rule: &CFRule) -> Result<(), Conflict> {
+    let row = table.entry(rule.0).or_default();
+
+    // The wildcard rule is taken on the letters without their own, so they
+    // must agree wherever both are present.
+    let other = row.iter().find(|(other, other_rule)| {
+        let shared = match (letter, **other) {
+            (ANY_TERMINAL, other) => other != END_TERMINAL,
+            (letter, ANY_TERMINAL) => letter != END_TERMINAL,
+            (letter, other) => letter == other,
+        };
+        shared && *other_rule != rule
+    });
+
+    if let Some((_, other_rule)) = other {
+        return Err(Conflict {
+            symbol: rule.0,
+            letter,
+            rules: (other_rule.clone(), rule.clone()),
+        });
+    }
+
+    row.insert(letter, rule.clone());
+    Ok(())
+}
+
+/// Attach the finished nodes to their parents, returning the root once it is
+/// finished.
+fn complete(nodes: &mut Vec<(CFRule, Vec<ParseTree>)>) -> Option<ParseTree> {
+    while let Some((rule, children)) = nodes.last() {
+        if children.len() < rule.1.chars().count() {
+            return None;
+        }
+
+        let (rule, children) = nodes.pop().unwrap();
+        let tree = ParseTree::Node { rule, children };
+
+        match nodes.last_mut() {
+            Some(parent) => parent.1.push(tree),
+            None => return Some(tree),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ll1_unit_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LL1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["a", "a+a*a", "(a+a)*a", "((a))"] {
+            let tree = parser.parse(word);
+            assert_eq!(tree.map(|tree| tree.symbol()), Ok('S'), "{word}");
+        }
+
+        let tree = parser.parse("a").expect("Parse unsuccessful");
+        let ParseTree::Node { rule, children } = tree else {
+            panic!("The root is a leaf");
+        };
+        assert_eq!(rule, ('S', "T".to_string()));
+        assert_eq!(
+            children.iter().map(ParseTree::symbol).collect::<String>(),
+            "T"
+        );
+
+        assert_eq!(parser.parse("a+"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(
+            parser.parse("a)"),
+            Err(ParseError::UnexpectedSymbol { pos: 1, found: ')' })
+        );
+        assert_eq!(
+            parser.parse("ab"),
+            Err(ParseError::UnexpectedSymbol { pos: 1, found: 'b' })
+        );
+    }
+
+    #[cfg(feature = "earley")]
+    #[test]
+    fn ll1_earley_test_1() {
+        let grammar = CFGrammar::from_str("SE\na+*()\nS->E\nE->E+E|E*E|(E)|a\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = LL1Parser::new();
+        assert!(parser.fit(&grammar).is_err());
+
+        let grammar = CFGrammar::from_str("SETF\na+*()\nS->E\nE->E+T|T\nT->T*F|F\nF->(E)|a\nS")
+            .expect("Failed to parse the grammar.")
+            .eliminate_left_recursion();
+        let mut other_parser = crate::earley::EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        other_parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in [
+            "a", "a+a", "a*(a+a)", "(a)*a+a", "a+", "*a", "(a", "a)(", "", "aa",
+        ] {
+            assert_eq!(
+                Parser::predict(&mut parser, word),
+                Parser::predict(&mut other_parser, word),
+                "{word}"
+            );
+        }
+
+        assert!(Parser::predict(&mut parser, "(a+a)*a"));
+    }
+
+    #[test]
+    fn ll1_should_fail_1() {
+        let grammar =
+            CFGrammar::from_str("SA\nab\nS->aA|ab\nA->b\nS").expect("Failed to parse the grammar.");
+        let err = LL1Parser::new().fit(&grammar).unwrap_err();
+        let conflict = err.downcast_ref::<Conflict>().expect("Not a conflict");
+        assert_eq!(conflict.symbol, 'S');
+        assert_eq!(conflict.letter, 'a');
+
+        let mut parser = LL1Parser::new();
+        assert_eq!(Parser::parse(&mut parser, "a"), Err(ParseError::NotFitted));
+        assert!(parser.fit(&grammar).is_err());
+        assert_eq!(Parser::parse(&mut parser, "b"), Err(ParseError::NotFitted));
+        assert_eq!(Parser::parse(&mut parser, "ab"), Err(ParseError::NotFitted));
+
+        parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+        assert!(parser.fit(&grammar).is_err());
+        assert_eq!(Parser::parse(&mut parser, "a"), Err(ParseError::NotFitted));
+    }
+
+    #[test]
+    fn ll1_limit_test_1() {
+        let grammar = CFGrammar::from_str("S\na\nS->aS|\nS").expect("Failed to parse the grammar.");
+        let mut parser = LL1Parser::with_limit(8);
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "aa"));
+        assert_eq!(
+            Parser::parse(&mut parser, "aaaaaa"),
+            Err(ParseError::LimitExceeded)
+        );
+    }
+
+    #[test]
+    fn ll1_wildcard_test_1() {
+        let grammar = CFGrammar::from_str("SA\nab\nS->aA|b\nA->.A|\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = LL1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a"));
+        assert!(Parser::predict(&mut parser, "azb!"));
+        assert!(!Parser::predict(&mut parser, "bz"));
+
+        let grammar =
+            CFGrammar::from_str("SA\nab\nS->A\nA->.|a\nS").expect("Failed to parse the grammar.");
+        assert!(LL1Parser::new().fit(&grammar).is_err());
+    }
+
+    fn get_test_grammar() -> CFGrammar {
+        CFGrammar::from_str("STRFQP\na+*()\nS->T\nT->FR\nR->+FR|\nF->PQ\nQ->*PQ|\nP->(T)|a\nS")
+            .expect("Failed to parse the grammar.")
+    }
+}
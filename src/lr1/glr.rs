@@ -0,0 +1,312 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+struct GssNode {
+    state: usize,
+    /// Previous nodes with the trees of the symbols between them.
+    edges: Vec<(usize, ParseTree)>,
+}
+
+/// Generalized LR parser following every action of conflicting cells on a
+/// graph-structured stack, so it accepts any context-free grammar.
+#[derive(Debug, Default, Clone)]
+pub struct GLRParser {
+    /// Action table of the canonical LR(1) automaton, allowing conflicts.
+    transitions: HashMap<usize, HashMap<char, Vec<LR1Action>>>,
+    /// Terminals of the fitted grammar, the only letters a word may consist of.
+    terminals: HashSet<char>,
+    /// Rules of the fitted grammar, indexed by the reduce actions.
+    rules: Vec<CFRule>,
+}
+
+impl Parser for GLRParser {
+    fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        self.reset();
+        let (states, gotos) = LR1Parser::get_states(grammar)?;
+        self.terminals = grammar.get_user_terminals();
+        self.rules = get_rules(grammar);
+        let indices = get_rule_indices(&self.rules);
+
+        for (state_mapped, state) in states.iter().enumerate() {
+            for situation in state.iter() {
+                let (letter, action) = if situation.pos < situation.size() {
+                    let letter = situation.nth(situation.pos);
+
+                    if !grammar.is_terminal(&letter) {
+                        continue;
+                    }
+
+                    (letter, LR1Action::Shift(gotos[&(state_mapped, letter)]))
+                } else if situation.rule.0 == START_RULE {
+                    (situation.lookahead, LR1Action::Accept)
+                } else {
+                    let index = indices[&situation.rule];
+                    (situation.lookahead, LR1Action::Reduce(index))
+                };
+                self.add_transition(state_mapped, letter, action);
+            }
+
+            for letter in grammar.non_terminals.iter() {
+                if let Some(goto_mapped) = gotos.get(&(state_mapped, *letter)) {
+                    self.add_transition(state_mapped, *letter, LR1Action::Shift(*goto_mapped));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build a parse tree of the word, one of many for ambiguous grammars.
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError> {
+        self.parse_tokens(&word.chars().collect::<Vec<_>>())
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl GLRParser {
+    pub fn new() -> Self {
+        Self {
+            transitions: HashMap::new(),
+            terminals: HashSet::new(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Check if the already tokenized word is in the language.
+    pub fn predict_tokens(&self, tokens: &[char]) -> bool {
+        self.parse_tokens(tokens).is_ok()
+    }
+
+    /// Build a parse tree of the already tokenized word.
+    pub fn parse_tokens(&self, letters: &[char]) -> Result<ParseTree, ParseError> {
+        if self.transitions.is_empty() {
+            return Err(ParseError::NotFitted);
+        }
+
+        check_letters(&self.terminals, letters)?;
+        let mut nodes = vec![GssNode {
+            state: 0,
+            edges: Vec::new(),
+        }];
+        let mut frontier = HashMap::from([(0, 0)]);
+
+        for pos in 0..=letters.len() {
+            let letter = letters.get(pos).copied().unwrap_or(END_TERMINAL);
+            self.reduce_all(&mut nodes, &mut frontier, letter);
+
+            if pos == letters.len() {
+                for node in frontier.values() {
+                    if self
+                        .get_actions(nodes[*node].state, letter)
+                        .contains(&LR1Action::Accept)
+                    {
+                        return nodes[*node]
+                            .edges
+                            .first()
+                            .map(|edge| edge.1.clone())
+                            .ok_or(ParseError::UnexpectedEnd);
+                    }
+                }
+
+                return Err(ParseError::UnexpectedEnd);
+            }
+
+            let mut new_frontier = HashMap::new();
+
+            for node in frontier.values() {
+                for action in self.get_letter_actions(nodes[*node].state, letter) {
+                    if let LR1Action::Shift(state) = action {
+                        let next = *new_frontier.entry(state).or_insert_with(|| {
+                            nodes.push(GssNode {
+                                state,
+                                edges: Vec::new(),
+                            });
+                            nodes.len() - 1
+                        });
+                        nodes[next].edges.push((*node, ParseTree::Leaf(letter)));
+                    }
+                }
+            }
+
+            if new_frontier.is_empty() {
+                return Err(ParseError::UnexpectedSymbol { pos, found: letter });
+            }
+
+            frontier = new_frontier;
+        }
+
+        Err(ParseError::UnexpectedEnd)
+    }
+
+    /// Apply all reductions possible on the lookahead to the topmost nodes.
+    fn reduce_all(
+        &self,
+        nodes: &mut Vec<GssNode>,
+        frontier: &mut HashMap<usize, usize>,
+        letter: char,
+    ) {
+        let mut queue: Vec<_> = frontier.values().copied().collect();
+
+        while let Some(node) = queue.pop() {
+            for action in self.get_letter_actions(nodes[node].state, letter) {
+                let LR1Action::Reduce(index) = action else {
+                    continue;
+                };
+                let rule = &self.rules[index];
+                let count = rule.1.chars().count();
+
+                for (prev, children) in Self::get_paths(nodes, node, count) {
+                    let Some(LR1Action::Shift(state)) =
+                        self.get_actions(nodes[prev].state, rule.0).first()
+                    else {
+                        continue;
+                    };
+                    let tree = ParseTree::Node {
+                        rule: rule.clone(),
+                        children,
+                    };
+
+                    match frontier.get(state) {
+                        Some(next) if nodes[*next].edges.iter().any(|edge| edge.0 == prev) => {}
+                        Some(next) => {
+                            // Reductions through the new edge must be redone.
+                            nodes[*next].edges.push((prev, tree));
+                            queue.extend(frontier.values());
+                        }
+                        None => {
+                            nodes.push(GssNode {
+                                state: *state,
+                                edges: vec![(prev, tree)],
+                            });
+                            frontier.insert(*state, nodes.len() - 1);
+                            queue.push(nodes.len() - 1);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Nodes reached by going `count` edges back, with the trees along the way.
+    fn get_paths(nodes: &[GssNode], node: usize, count: usize) -> Vec<(usize, Vec<ParseTree>)> {
+        if count == 0 {
+            return vec![(node, Vec::new())];
+        }
+
+        let mut paths = Vec::new();
+
+        for (prev, tree) in nodes[node].edges.iter() {
+            for (start, mut children) in Self::get_paths(nodes, *prev, count - 1) {
+                children.push(tree.clone());
+                paths.push((start, children));
+            }
+        }
+
+        paths
+    }
+
+    fn get_actions(&self, state: usize, letter: char) -> &[LR1Action] {
+        self.transitions
+            .get(&state)
+            .and_then(|actions| actions.get(&letter))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Actions on the letter of the word or the end of it, with the wildcard ones.
+    fn get_letter_actions(&self, state: usize, letter: char) -> Vec<LR1Action> {
+        let mut actions = Vec::new();
+
+        // Letters that are not terminals must not hit the gotos.
+        if letter == END_TERMINAL || (letter != ANY_TERMINAL && self.terminals.contains(&letter)) {
+            actions.extend_from_slice(self.get_actions(state, letter));
+        }
+
+        if letter != END_TERMINAL {
+            for action in self.get_actions(state, ANY_TERMINAL) {
+                if !actions.contains(action) {
+                    actions.push(*action);
+                }
+            }
+        }
+
+        actions
+    }
+
+    fn add_transition(&mut self, state: usize, letter: char, action: LR1Action) {
+        let actions = self
+            .transitions
+            .entry(state)
+            .or_default()
+            .entry(letter)
+            .or_default();
+
+        if !actions.contains(&action) {
+            actions.push(action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glr_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\na\nS->Sa\nS->a\nS->\nS").expect("Failed to parse the grammar.");
+        assert!(LR1Parser::new().fit(&grammar).is_err());
+        let mut parser = GLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["", "a", "aaa"] {
+            assert!(Parser::predict(&mut parser, word));
+        }
+
+        assert!(!Parser::predict(&mut parser, "b"));
+        assert!(!Parser::predict(&mut parser, "aab"));
+    }
+
+    #[test]
+    fn glr_unit_test_2() {
+        let grammar =
+            CFGrammar::from_str("E\n+a\nE->E+E\nE->a\nE").expect("Failed to parse the grammar.");
+        assert!(LR1Parser::new().fit(&grammar).is_err());
+        let mut parser = GLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let tree = parser.parse("a+a+a").expect("Parse unsuccessful");
+        assert_eq!(tree.symbol(), 'E');
+        assert_eq!(parser.parse("a+"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(parser.parse("a+a+"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(
+            parser.parse("a++"),
+            Err(ParseError::UnexpectedSymbol { pos: 2, found: '+' })
+        );
+    }
+
+    #[test]
+    fn glr_unit_test_3() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AS\nS->B\nA->\nA->a\nB->b\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = GLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "b"));
+        assert!(Parser::predict(&mut parser, "aab"));
+        assert!(!Parser::predict(&mut parser, "ba"));
+    }
+
+    #[test]
+    fn glr_should_fail_1() {
+        let mut grammar =
+            CFGrammar::from_str("S\na\nS->a\nS").expect("Failed to parse the grammar.");
+        let mut parser = GLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "a"));
+        grammar.rules.insert(START_RULE, "S".to_string());
+        assert!(parser.fit(&grammar).is_err());
+        assert!(!Parser::predict(&mut parser, "a"));
+        assert_eq!(parser.parse("a"), Err(ParseError::NotFitted));
+    }
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeMap;
+
+use anyhow::Context;
+
+use super::*;
+
+type LR1Core = BTreeSet<(CFRule, usize)>;
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LALR1Parser {
+    /// Action table in the LR(1) format.
+    parser: LR1Parser,
+}
+
+impl Parser for LALR1Parser {
+    fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        self.parser.reset();
+        let (states, gotos) = Self::get_states(grammar)?;
+        let mut parser = self.parser.clone();
+        parser
+            .build_table(grammar, &states, &gotos)
+            .with_context(|| "The given grammar is not LALR(1).")?;
+        self.parser = parser;
+        Ok(())
+    }
+
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError> {
+        self.parser.parse(word)
+    }
+
+    fn reset(&mut self) {
+        self.parser.reset();
+    }
+}
+
+#[cfg(feature = "lr-internals")]
+impl LALR1Parser {
+    /// States of the canonical automaton merged by their cores.
+    pub fn states(
+        grammar: &CFGrammar,
+    ) -> Result<(Vec<BTreeSet<LR1Situation>>, LR1Gotos), anyhow::Error> {
+        Self::get_states(grammar)
+    }
+
+    /// Table shared with the canonical parser.
+    pub fn table(&self) -> &LR1Parser {
+        &self.parser
+    }
+}
+
+impl LALR1Parser {
+    pub fn new() -> Self {
+        Self {
+            parser: LR1Parser::new(),
+        }
+    }
+
+    /// Parser giving up on words that take more than `limit` moves.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            parser: LR1Parser::with_limit(limit),
+        }
+    }
+
+    /// Check if the already tokenized word is in the language.
+    pub fn predict_tokens(&self, tokens: &[char]) -> bool {
+        self.parser.predict_tokens(tokens)
+    }
+
+    /// Check the word either as a whole or as a prefix.
+    pub fn matches(&self, word: &str, mode: MatchMode) -> bool {
+        self.parser.matches(word, mode)
+    }
+
+    /// Length of the longest prefix of the word in the language, if there is one.
+    pub fn longest_match(&self, word: &str) -> Option<usize> {
+        self.parser.longest_match(word)
+    }
+
+    /// Rules applied in the rightmost derivation of the word from the start.
+    pub fn rightmost_derivation(&self, word: &str) -> Option<Vec<CFRule>> {
+        self.parser.rightmost_derivation(word)
+    }
+
+    /// Merge the states with the same actions on every lookahead.
+    pub fn minimize(&mut self) {
+        self.parser.minimize()
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.parser.num_states()
+    }
+
+    pub fn num_transitions(&self) -> usize {
+        self.parser.num_transitions()
+    }
+
+    pub fn num_shifts(&self) -> usize {
+        self.parser.num_shifts()
+    }
+
+    pub fn num_reduces(&self) -> usize {
+        self.parser.num_reduces()
+    }
+
+    fn get_core(state: &BTreeSet<LR1Situation>) -> LR1Core {
+        state
+            .iter()
+            .map(|situation| (situation.rule.clone(), situation.pos))
+            .collect()
+    }
+
+    /// Merge the canonical states with the same core, keeping the start one first.
+    fn get_states(
+        grammar: &CFGrammar,
+    ) -> Result<(Vec<BTreeSet<LR1Situation>>, LR1Gotos), anyhow::Error> {
+        let (states, gotos) = LR1Parser::get_states(grammar)?;
+        let mut mapping = BTreeMap::<LR1Core, usize>::new();
+        let mut merged = Vec::<BTreeSet<LR1Situation>>::new();
+        let mut located = Vec::new();
+
+        for state in states {
+            let next = merged.len();
+            let index = *mapping.entry(Self::get_core(&state)).or_insert(next);
+
+            if index == merged.len() {
+                merged.push(BTreeSet::new());
+            }
+
+            merged[index].extend(state);
+            located.push(index);
+        }
+
+        let gotos = gotos
+            .into_iter()
+            .map(|((state, symbol), next)| ((located[state], symbol), located[next]))
+            .collect();
+        Ok((merged, gotos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lr1::tests::get_test_grammar;
+
+    #[test]
+    fn lalr1_unit_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LALR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "cdd"));
+        assert!(Parser::predict(&mut parser, "ccdcd"));
+        assert!(!Parser::predict(&mut parser, "ddd"));
+    }
+
+    #[test]
+    fn lalr1_unit_test_2() {
+        let grammar = get_test_grammar();
+        let states = LR1Parser::get_states(&grammar).expect("Failed to build the states.");
+        assert_eq!(states.0.len(), 10);
+        let states = LALR1Parser::get_states(&grammar).expect("Failed to build the states.");
+        assert_eq!(states.0.len(), 7);
+    }
+
+    #[test]
+    fn lalr1_should_fail_1() {
+        let grammar =
+            CFGrammar::from_str("SAB\nabcde\nS->aAd\nS->bBd\nS->aBe\nS->bAe\nA->c\nB->c\nS")
+                .expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::new();
+        assert!(parser.fit(&grammar).is_ok());
+        let mut parser = LALR1Parser::new();
+        assert!(parser.fit(&grammar).is_err());
+    }
+}
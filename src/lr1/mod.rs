@@ -1,12 +1,22 @@
 use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::fmt;
+
+use anyhow::Context;
 
 use super::*;
 
+pub mod glr;
+pub mod lalr;
+pub mod slr;
+
+/// Item of the canonical automaton: a rule with a dot and a lookahead.
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct LR1Situation {
+pub struct LR1Situation {
     rule: CFRule,
     pos: usize,
     lookahead: char,
+    /// Number of symbols in the right part of the rule.
+    size: usize,
 }
 
 impl LR1Situation {
@@ -15,171 +25,652 @@ impl LR1Situation {
             rule: rule.clone(),
             pos,
             lookahead,
+            size: rule.1.chars().count(),
         }
     }
 
+    fn size(&self) -> usize {
+        self.size
+    }
+
     fn nth(&self, pos: usize) -> char {
         self.rule.1.chars().nth(pos).unwrap_or_default()
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum LR1Action {
+#[cfg(feature = "lr-internals")]
+impl LR1Situation {
+    pub fn rule(&self) -> &CFRule {
+        &self.rule
+    }
+
+    /// Number of symbols of the right part before the dot.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    pub fn lookahead(&self) -> char {
+        self.lookahead
+    }
+}
+
+impl fmt::Display for LR1Situation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let before: String = self.rule.1.chars().take(self.pos).collect();
+        let after: String = self.rule.1.chars().skip(self.pos).collect();
+        write!(
+            f,
+            "{} -> {}·{}, {}",
+            self.rule.0.escape_debug(),
+            before.escape_debug(),
+            after.escape_debug(),
+            self.lookahead.escape_debug()
+        )
+    }
+}
+
+/// Transitions between the states of an automaton by their indices.
+pub type LR1Gotos = HashMap<(usize, char), usize>;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Move of the stack machine in a state on a lookahead.
+pub enum LR1Action {
+    /// The word is rejected.
+    #[default]
     NoAction,
+    /// Push the symbol and go to the state.
     Shift(usize),
-    Reduce(usize, char),
+    /// Pop as many states as the rule with the index has symbols in its right
+    /// part and push the non-terminal of its left part.
+    Reduce(usize),
+    /// The word is derived from the start.
     Accept,
 }
 
-impl Default for LR1Action {
-    fn default() -> Self {
-        LR1Action::NoAction
+/// Weakest class of the LR grammars a grammar belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParsingClass {
+    LR0,
+    SLR1,
+    LALR1,
+    LR1,
+    /// The grammar is ambiguous or needs more than one letter of lookahead.
+    NotLR1,
+}
+
+impl CFGrammar {
+    /// Try the automata from the weakest to the strongest one and report the
+    /// first without conflicts.
+    pub fn parsing_class(&self) -> ParsingClass {
+        if slr::SLRParser::is_lr0(self) {
+            ParsingClass::LR0
+        } else if slr::SLRParser::new().fit(self).is_ok() {
+            ParsingClass::SLR1
+        } else if lalr::LALR1Parser::new().fit(self).is_ok() {
+            ParsingClass::LALR1
+        } else if LR1Parser::check_lr1(self).is_ok_and(|conflict| conflict.is_none()) {
+            ParsingClass::LR1
+        } else {
+            ParsingClass::NotLR1
+        }
     }
 }
 
+/// Two actions competing for the same cell of the action table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    pub state: usize,
+    pub symbol: char,
+    /// The action already in the cell and the one added to it.
+    pub actions: (LR1Action, LR1Action),
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.actions {
+            (LR1Action::Shift(_), _) | (_, LR1Action::Shift(_)) => "Shift/reduce",
+            _ => "Reduce/reduce",
+        };
+        write!(
+            f,
+            "{kind} conflict in state {} on {:?} between {:?} and {:?}.",
+            self.state, self.symbol, self.actions.0, self.actions.1
+        )
+    }
+}
+
+impl std::error::Error for Conflict {}
+
 #[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LR1Parser {
     transitions: HashMap<usize, HashMap<char, LR1Action>>,
     start: usize,
+    /// Terminals of the fitted grammar, the only letters a word may consist of.
+    terminals: HashSet<char>,
+    /// Rules of the fitted grammar, indexed by the reduce actions.
+    rules: Vec<CFRule>,
+    /// Rendered items of every state, kept for debugging.
+    items: Vec<Vec<String>>,
+    /// Maximal number of moves of the stack machine per word.
+    limit: Option<usize>,
 }
 
 impl Parser for LR1Parser {
     fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
-        let states = Self::get_states(grammar);
-        let mut mapping = HashMap::new();
+        self.reset();
+        let (states, gotos) = Self::get_states(grammar)?;
+        // The table is swapped in only when complete, a failed fit leaves the
+        // parser unfitted.
+        let mut parser = self.clone();
+        parser
+            .build_table(grammar, &states, &gotos)
+            .with_context(|| "The given grammar is not LR(1).")?;
+        *self = parser;
+        Ok(())
+    }
+
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError> {
+        self.parse_tokens(&word.chars().collect::<Vec<_>>())
+    }
+
+    fn reset(&mut self) {
+        *self = LR1Parser {
+            limit: self.limit,
+            ..LR1Parser::new()
+        };
+    }
+}
+
+#[cfg(feature = "lr-internals")]
+impl LR1Parser {
+    /// States of the canonical automaton of the grammar with the start one
+    /// first, and the transitions between them on every symbol.
+    pub fn states(
+        grammar: &CFGrammar,
+    ) -> Result<(Vec<BTreeSet<LR1Situation>>, LR1Gotos), anyhow::Error> {
+        Self::get_states(grammar)
+    }
+
+    /// Action table by the state and the lookahead, gotos on non-terminals are
+    /// stored as shifts.
+    pub fn transitions(&self) -> &HashMap<usize, HashMap<char, LR1Action>> {
+        &self.transitions
+    }
+
+    pub fn start_state(&self) -> usize {
+        self.start
+    }
+}
+
+impl LR1Parser {
+    pub fn new() -> Self {
+        Self {
+            start: 0,
+            transitions: HashMap::new(),
+            terminals: HashSet::new(),
+            rules: Vec::new(),
+            items: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Parser giving up on words that take more than `limit` moves.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// First conflict of the canonical automaton of the grammar, `None` if it has
+    /// none. Fails if the automaton can not be built, e.g. for grammars without a
+    /// single start rule.
+    pub fn check_lr1(grammar: &CFGrammar) -> Result<Option<Conflict>, anyhow::Error> {
+        let (states, gotos) = Self::get_states(grammar)?;
+
+        match Self::new().build_table(grammar, &states, &gotos) {
+            Err(err) => match err.downcast_ref::<Conflict>() {
+                Some(conflict) => Ok(Some(*conflict)),
+                None => Err(err),
+            },
+            Ok(()) => Ok(None),
+        }
+    }
+
+    /// Rules of the fitted grammar in the order of the indices of the reduce
+    /// actions.
+    pub fn rules(&self) -> &[CFRule] {
+        &self.rules
+    }
+
+    /// Number of states of the automaton.
+    pub fn num_states(&self) -> usize {
+        self.transitions.len()
+    }
+
+    /// Number of actions in the table, gotos on non-terminals included.
+    pub fn num_transitions(&self) -> usize {
+        self.count_actions(|action| action != LR1Action::NoAction)
+    }
+
+    /// Number of shift actions, gotos on non-terminals included.
+    pub fn num_shifts(&self) -> usize {
+        self.count_actions(|action| matches!(action, LR1Action::Shift(_)))
+    }
+
+    /// Number of reduce actions.
+    pub fn num_reduces(&self) -> usize {
+        self.count_actions(|action| matches!(action, LR1Action::Reduce(..)))
+    }
+
+    fn count_actions(&self, predicate: impl Fn(LR1Action) -> bool) -> usize {
+        self.transitions
+            .values()
+            .flat_map(HashMap::values)
+            .filter(|action| predicate(**action))
+            .count()
+    }
+
+    /// Merge the states with the same actions on every lookahead, where shifts
+    /// are the same if they lead to merged states and reduces if they use the
+    /// same rule, so parse trees do not change. The merged state keeps the items
+    /// of all of them.
+    ///
+    /// The states of a table built by `fit` already differ in their items, and
+    /// so in what they reduce after which symbols, so only tables changed by
+    /// hand or loaded from elsewhere shrink.
+    pub fn minimize(&mut self) {
+        let empty = HashMap::new();
+        let states: BTreeSet<_> = self
+            .transitions
+            .iter()
+            .flat_map(|(state, actions)| {
+                actions
+                    .values()
+                    .filter_map(|action| match action {
+                        LR1Action::Shift(next) => Some(*next),
+                        _ => None,
+                    })
+                    .chain([*state])
+            })
+            .chain([self.start])
+            .collect();
+        let get_actions = |state: usize, blocks: &HashMap<usize, usize>| {
+            let mut actions: Vec<_> = self
+                .transitions
+                .get(&state)
+                .unwrap_or(&empty)
+                .iter()
+                .map(|(letter, action)| match action {
+                    LR1Action::Shift(next) => (*letter, LR1Action::Shift(blocks[next])),
+                    action => (*letter, *action),
+                })
+                .collect();
+            actions.sort_by_key(|(letter, _)| *letter);
+            actions
+        };
+        let mut blocks: HashMap<_, _> = states.iter().map(|state| (*state, 0)).collect();
+        let mut num_blocks = 1;
 
-        for (i, state) in states.iter().enumerate() {
-            mapping.insert(state, i);
+        // Split the blocks until the states of each one act the same.
+        loop {
+            let mut signatures = HashMap::new();
+            let new_blocks: HashMap<_, _> = states
+                .iter()
+                .map(|state| {
+                    let signature = (blocks[state], get_actions(*state, &blocks));
+                    let next_id = signatures.len();
+                    (*state, *signatures.entry(signature).or_insert(next_id))
+                })
+                .collect();
+            blocks = new_blocks;
+
+            if signatures.len() == num_blocks {
+                break;
+            }
+
+            num_blocks = signatures.len();
         }
 
+        let mut transitions = HashMap::new();
+        let mut items = vec![Vec::new(); num_blocks];
+
         for state in states.iter() {
-            let state_mapped = mapping.get(state).unwrap();
+            if self.transitions.contains_key(state) {
+                transitions
+                    .entry(blocks[state])
+                    .or_insert_with(|| get_actions(*state, &blocks).into_iter().collect());
+            }
 
-            for situation in state.iter() {
-                if situation.pos < situation.rule.1.len() {
-                    if grammar.is_terminal(situation.nth(situation.pos)) {
-                        let letter = situation.nth(situation.pos);
-                        let goto = Self::goto(grammar, state, letter);
-                        let goto_mapped = mapping.get(&goto).unwrap();
-                        self.add_transition(
-                            *state_mapped,
-                            letter,
-                            &LR1Action::Shift(*goto_mapped),
-                        )?;
-                    }
+            for item in self.items.get(*state).into_iter().flatten() {
+                if !items[blocks[state]].contains(item) {
+                    items[blocks[state]].push(item.clone());
+                }
+            }
+        }
 
-                    if situation.rule.0 == START_RULE {
-                        self.start = *state_mapped;
-                    }
+        self.start = blocks[&self.start];
+        self.transitions = transitions;
+        self.items = items;
+    }
 
-                    continue;
+    /// Automaton in the Graphviz DOT format. Nodes are labeled with their items
+    /// and actions on the lookahead, edges with the shifted symbols.
+    pub fn to_dot(&self) -> String {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut states: Vec<_> = self.transitions.keys().copied().collect();
+        states.sort();
+        let mut dot = String::from("digraph LR {\n    node [shape=box];\n");
+
+        for state in states.iter() {
+            let mut label = format!("{state}\\l");
+
+            for item in self.items.get(*state).into_iter().flatten() {
+                label += &format!("{}\\l", escape(item));
+            }
+
+            let mut actions: Vec<_> = self.transitions[state].iter().collect();
+            actions.sort_by_key(|(letter, _)| **letter);
+
+            for (letter, action) in actions.iter() {
+                let letter = escape(&letter.escape_debug().to_string());
+
+                match action {
+                    LR1Action::Reduce(index) => {
+                        let (rule_left, rule_right) = &self.rules[*index];
+                        let rule = format!("{} -> {}", rule_left, rule_right)
+                            .escape_debug()
+                            .to_string();
+                        label += &format!("on {letter}: reduce {}\\l", escape(&rule));
+                    }
+                    LR1Action::Accept => label += &format!("on {letter}: accept\\l"),
+                    _ => {}
                 }
+            }
 
-                if situation.rule.0 == START_RULE && situation.lookahead == END_TERMINAL {
-                    let letter = situation.lookahead;
-                    self.add_transition(*state_mapped, letter, &LR1Action::Accept)?;
-                } else {
-                    let letter = situation.lookahead;
-                    let size = situation.rule.1.len();
-                    let symbol = situation.rule.0;
-                    self.add_transition(*state_mapped, letter, &LR1Action::Reduce(size, symbol))?;
+            dot += &format!("    {state} [label=\"{label}\"];\n");
+
+            for (letter, action) in actions {
+                if let LR1Action::Shift(next) = action {
+                    let letter = escape(&letter.escape_debug().to_string());
+                    dot += &format!("    {state} -> {next} [label=\"{letter}\"];\n");
                 }
             }
+        }
 
-            for letter in grammar.non_terminals.iter() {
-                let goto = Self::goto(grammar, state, *letter);
+        dot + "}\n"
+    }
+
+    /// Check if the already tokenized word is in the language.
+    pub fn predict_tokens(&self, tokens: &[char]) -> bool {
+        self.parse_tokens(tokens).is_ok()
+    }
+
+    /// Build the parse tree of the already tokenized word.
+    pub fn parse_tokens(&self, letters: &[char]) -> Result<ParseTree, ParseError> {
+        self.run(letters, |_, _, _| {})
+    }
+
+    /// States, lookaheads and actions of the moves made while parsing the word,
+    /// up to the rejecting one if the word is not in the language. Only the moves
+    /// of the action table are listed, the gotos on the reduced non-terminals are
+    /// not, so every lookahead is a letter of the word or the end of it.
+    pub fn trace(&self, word: &str) -> Vec<(usize, char, LR1Action)> {
+        let mut steps = Vec::new();
+        let _ = self.run(
+            &word.chars().collect::<Vec<_>>(),
+            |state, letter, action| steps.push((state, letter, action)),
+        );
+        steps
+    }
+
+    /// Check the word either as a whole or as a prefix.
+    ///
+    /// In the prefix mode the letters are fed until one of them has no action,
+    /// so the word is accepted if the parser never hits an error on it.
+    pub fn matches(&self, word: &str, mode: MatchMode) -> bool {
+        match mode {
+            MatchMode::Exact => self.predict_tokens(&word.chars().collect::<Vec<_>>()),
+            MatchMode::Prefix => {
+                let mut states = vec![self.start];
+                !self.transitions.is_empty()
+                    && word.chars().all(|letter| {
+                        check_letters(&self.terminals, &[letter]).is_ok()
+                            && self.feed(&mut states, letter) != LR1Action::NoAction
+                    })
+            }
+        }
+    }
+
+    /// Length of the longest prefix of the word in the language, if there is one.
+    pub fn longest_match(&self, word: &str) -> Option<usize> {
+        let mut states = vec![self.start];
+        let mut longest = None;
+
+        for (pos, letter) in word.chars().chain([END_TERMINAL]).enumerate() {
+            if self.feed(&mut states.clone(), END_TERMINAL) == LR1Action::Accept {
+                longest = Some(pos);
+            }
+
+            if check_letters(&self.terminals, &[letter]).is_err()
+                || self.feed(&mut states, letter) == LR1Action::NoAction
+            {
+                break;
+            }
+        }
+
+        longest
+    }
+
+    /// Make the reduces on the lookahead followed by its shift, returning the
+    /// last action.
+    fn feed(&self, states: &mut Vec<usize>, letter: char) -> LR1Action {
+        let get_action = |state, letter| {
+            self.transitions
+                .get(&state)
+                .and_then(|actions| actions.get(&letter))
+                .copied()
+                .unwrap_or_default()
+        };
 
-                if !goto.is_empty() {
-                    let goto_mapped = mapping.get(&goto).unwrap();
-                    self.add_transition(*state_mapped, *letter, &LR1Action::Shift(*goto_mapped))?;
+        loop {
+            match self.get_letter_action(*states.last().unwrap(), letter) {
+                LR1Action::Shift(state) => {
+                    states.push(state);
+                    return LR1Action::Shift(state);
+                }
+                LR1Action::Reduce(index) => {
+                    let (symbol, rule_right) = &self.rules[index];
+                    states.truncate(states.len() - rule_right.chars().count());
+
+                    match get_action(*states.last().unwrap(), *symbol) {
+                        LR1Action::Shift(state) => states.push(state),
+                        _ => return LR1Action::NoAction,
+                    }
                 }
+                action => return action,
             }
         }
+    }
 
-        Ok(())
+    /// Action on the letter of the word or the end of it, the wildcard one if the
+    /// letter has none.
+    fn get_letter_action(&self, state: usize, letter: char) -> LR1Action {
+        let get_action = |letter| {
+            self.transitions
+                .get(&state)
+                .and_then(|actions| actions.get(&letter))
+                .copied()
+                .unwrap_or_default()
+        };
+
+        if letter == END_TERMINAL {
+            return get_action(END_TERMINAL);
+        }
+
+        // Letters that are not terminals must not hit the gotos.
+        let action = if letter != ANY_TERMINAL && self.terminals.contains(&letter) {
+            get_action(letter)
+        } else {
+            LR1Action::NoAction
+        };
+
+        if action == LR1Action::NoAction && self.terminals.contains(&ANY_TERMINAL) {
+            get_action(ANY_TERMINAL)
+        } else {
+            action
+        }
     }
 
-    fn predict(&mut self, word: &str) -> bool {
+    /// Rules applied in the rightmost derivation of the word from the start, the
+    /// reductions of the parser in the reverse order.
+    pub fn rightmost_derivation(&self, word: &str) -> Option<Vec<CFRule>> {
+        let tree = self.parse_tokens(&word.chars().collect::<Vec<_>>()).ok()?;
+        let mut rules = Vec::new();
+        let mut stack = vec![&tree];
+
+        while let Some(node) = stack.pop() {
+            if let ParseTree::Node { rule, children } = node {
+                rules.push(rule.clone());
+                stack.extend(children.iter());
+            }
+        }
+
+        Some(rules)
+    }
+
+    /// Run the stack machine on the letters, calling `on_step` before every move
+    /// of the action table.
+    fn run<F>(&self, letters: &[char], mut on_step: F) -> Result<ParseTree, ParseError>
+    where
+        F: FnMut(usize, char, LR1Action),
+    {
+        if self.transitions.is_empty() {
+            return Err(ParseError::NotFitted);
+        }
+
+        check_letters(&self.terminals, letters)?;
         let mut queue = VecDeque::from([self.start]);
-        let mut stack = VecDeque::from_iter(word.chars().rev());
+        let mut stack = VecDeque::from_iter(letters.iter().copied().rev());
         stack.push_front(END_TERMINAL);
+        let mut trees = Vec::new();
+        let mut pos = 0;
+        let mut reduced = 0;
+        let mut steps = 0;
 
         while !(queue.is_empty() || stack.is_empty()) {
-            let action = self
-                .transitions
-                .entry(*queue.back().unwrap())
-                .or_default()
-                .entry(*stack.back().unwrap())
-                .or_default();
+            steps += 1;
 
-            match *action {
+            if self.limit.is_some_and(|limit| steps > limit) {
+                return Err(ParseError::LimitExceeded);
+            }
+
+            let action = if reduced > 0 {
+                self.transitions
+                    .get(queue.back().unwrap())
+                    .and_then(|actions| actions.get(stack.back().unwrap()))
+                    .copied()
+                    .unwrap_or_default()
+            } else {
+                self.get_letter_action(*queue.back().unwrap(), *stack.back().unwrap())
+            };
+
+            if reduced == 0 {
+                on_step(*queue.back().unwrap(), *stack.back().unwrap(), action);
+            }
+
+            match action {
                 LR1Action::Shift(state) => {
                     queue.push_back(state);
-                    stack.pop_back();
+                    let letter = stack.pop_back().unwrap();
+
+                    if reduced > 0 {
+                        reduced -= 1;
+                    } else {
+                        trees.push(ParseTree::Leaf(letter));
+                        pos += 1;
+                    }
                 }
-                LR1Action::Reduce(count, symbol) => {
+                LR1Action::Reduce(index) => {
+                    let rule = &self.rules[index];
+                    let count = rule.1.chars().count();
                     queue.drain((queue.len() - count)..);
-                    stack.push_back(symbol);
+                    let children = trees.split_off(trees.len() - count);
+                    trees.push(ParseTree::Node {
+                        rule: rule.clone(),
+                        children,
+                    });
+                    stack.push_back(rule.0);
+                    reduced += 1;
                 }
                 LR1Action::Accept => {
-                    return true;
+                    return trees.pop().ok_or(ParseError::UnexpectedEnd);
                 }
                 LR1Action::NoAction => {
-                    return false;
+                    break;
                 }
             }
         }
 
-        false
+        match letters.get(pos) {
+            Some(found) => Err(ParseError::UnexpectedSymbol { pos, found: *found }),
+            None => Err(ParseError::UnexpectedEnd),
+        }
     }
-}
 
-impl LR1Parser {
-    pub fn new() -> Self {
-        Self {
-            start: 0,
-            transitions: HashMap::new(),
+    /// Check if the word is in the language, returning the position of the first
+    /// unexpected letter otherwise (the word length if it ended unexpectedly).
+    /// There is no position if the parser is not fitted or hit its limit.
+    pub fn predict_at(&mut self, word: &str) -> Result<(), Option<usize>> {
+        match Parser::parse(self, word) {
+            Ok(_) => Ok(()),
+            Err(ParseError::NotFitted | ParseError::LimitExceeded) => Err(None),
+            Err(ParseError::UnexpectedSymbol { pos, .. }) => Err(Some(pos)),
+            Err(ParseError::UnexpectedEnd) => Err(Some(word.chars().count())),
         }
     }
 
-    fn closure(grammar: &CFGrammar, state: &BTreeSet<LR1Situation>) -> BTreeSet<LR1Situation> {
+    /// Same as `predict_at` with the position translated into a line and a
+    /// column of the word.
+    pub fn predict_position(&mut self, word: &str) -> Result<(), Option<Position>> {
+        self.predict_at(word)
+            .map_err(|index| index.map(|index| Position::at(word, index)))
+    }
+
+    fn closure(
+        grammar: &CFGrammar,
+        first_sets: &HashMap<char, HashSet<char>>,
+        state: &BTreeSet<LR1Situation>,
+    ) -> BTreeSet<LR1Situation> {
         let mut new_state = state.clone();
-        let mut prev_diff = new_state.clone();
+        let mut queue: VecDeque<_> = state.iter().cloned().collect();
 
-        loop {
-            let mut curr_diff = BTreeSet::new();
+        while let Some(situation) = queue.pop_front() {
+            if situation.pos >= situation.size() {
+                continue;
+            }
 
-            for situation in prev_diff.iter() {
-                let rule_left = if situation.pos >= situation.rule.1.len() {
-                    continue;
-                } else {
-                    situation.nth(situation.pos)
-                };
+            let rule_left = situation.nth(situation.pos);
+            let Some(rules) = grammar.rules.get_vec(&rule_left) else {
+                continue;
+            };
+            let mut lookup: String = situation.rule.1.chars().skip(situation.pos + 1).collect();
+            lookup.push(situation.lookahead);
+            let first = CFGrammar::first_with(first_sets, &lookup);
+
+            for rule_right in rules.iter() {
+                for symbol in first.iter() {
+                    let new_situation =
+                        LR1Situation::new(&(rule_left, rule_right.clone()), 0, *symbol);
 
-                if let Some(rules) = grammar.rules.get_vec(&rule_left) {
-                    for rule_right in rules.iter() {
-                        let mut lookup: String =
-                            situation.rule.1.chars().skip(situation.pos + 1).collect();
-                        lookup.push(situation.lookahead);
-                        let first = Self::get_first(grammar, &lookup);
-
-                        for symbol in first.iter() {
-                            let new_situation =
-                                LR1Situation::new(&(rule_left, rule_right.clone()), 0, *symbol);
-
-                            if new_state.insert(new_situation.clone()) {
-                                curr_diff.insert(new_situation);
-                            }
-                        }
+                    if !new_state.contains(&new_situation) {
+                        new_state.insert(new_situation.clone());
+                        queue.push_back(new_situation);
                     }
                 }
             }
-
-            if curr_diff.len() == 0 {
-                break;
-            } else {
-                prev_diff = curr_diff;
-            }
         }
 
         new_state
@@ -187,14 +678,14 @@ impl LR1Parser {
 
     fn goto(
         grammar: &CFGrammar,
+        first_sets: &HashMap<char, HashSet<char>>,
         state: &BTreeSet<LR1Situation>,
         symbol: char,
     ) -> BTreeSet<LR1Situation> {
         let new_state = state
             .iter()
             .filter_map(|situation| {
-                if situation.pos >= situation.rule.1.len() || situation.nth(situation.pos) != symbol
-                {
+                if situation.pos >= situation.size() || situation.nth(situation.pos) != symbol {
                     None
                 } else {
                     Some(LR1Situation::new(
@@ -205,74 +696,105 @@ impl LR1Parser {
                 }
             })
             .collect();
-        Self::closure(grammar, &new_state)
-    }
-
-    fn get_first(grammar: &CFGrammar, lookup: &str) -> HashSet<char> {
-        let mut visited = HashSet::new();
-        Self::dfs(grammar, &mut visited, lookup)
+        Self::closure(grammar, first_sets, &new_state)
     }
 
-    fn dfs(grammar: &CFGrammar, visited: &mut HashSet<CFRule>, lookup: &str) -> HashSet<char> {
-        for symbol in lookup.chars() {
-            if grammar.terminals.contains(&symbol) {
-                return HashSet::from([symbol]);
-            }
+    /// States of the canonical automaton with the start one first, and the
+    /// transitions between them, each computed once.
+    fn get_states(
+        grammar: &CFGrammar,
+    ) -> Result<(Vec<BTreeSet<LR1Situation>>, LR1Gotos), anyhow::Error> {
+        let first_sets = grammar.get_first_sets();
+        let mut states = vec![Self::closure(
+            grammar,
+            &first_sets,
+            &BTreeSet::<_>::from([LR1Situation::new(
+                &grammar.get_start_rule()?,
+                0,
+                END_TERMINAL,
+            )]),
+        )];
+        let mut mapping = HashMap::from([(states[0].clone(), 0)]);
+        let mut gotos = LR1Gotos::new();
+        let mut queue = VecDeque::from([0]);
 
-            let rule_left = symbol;
-            let mut found = HashSet::new();
+        while let Some(curr) = queue.pop_front() {
+            let symbols: BTreeSet<_> = states[curr]
+                .iter()
+                .filter(|situation| situation.pos < situation.size())
+                .map(|situation| situation.nth(situation.pos))
+                .collect();
 
-            if let Some(rules) = grammar.rules.get_vec(&rule_left) {
-                for rule_right in rules.iter() {
-                    if visited.insert((rule_left, rule_right.clone())) {
-                        found.extend(&Self::dfs(grammar, visited, &rule_right));
+            for symbol in symbols {
+                let goto = Self::goto(grammar, &first_sets, &states[curr], symbol);
+                let next = match mapping.get(&goto) {
+                    Some(next) => *next,
+                    None => {
+                        mapping.insert(goto.clone(), states.len());
+                        queue.push_back(states.len());
+                        states.push(goto);
+                        states.len() - 1
                     }
-                }
-            }
-
-            if !found.is_empty() {
-                return found;
+                };
+                gotos.insert((curr, symbol), next);
             }
         }
 
-        HashSet::new()
+        Ok((states, gotos))
     }
 
-    fn get_states(grammar: &CFGrammar) -> BTreeSet<BTreeSet<LR1Situation>> {
-        let mut states = BTreeSet::new();
-        states.insert(Self::closure(
-            grammar,
-            &BTreeSet::<_>::from([LR1Situation::new(
-                &grammar.get_start_rule(),
-                0,
-                END_TERMINAL,
-            )]),
-        ));
-        let mut prev_diff = states.clone();
-        let mut all_symbols = Vec::from_iter(grammar.terminals.iter().copied());
-        all_symbols.extend(grammar.non_terminals.iter());
-
-        loop {
-            let mut curr_diff = BTreeSet::new();
+    fn build_table(
+        &mut self,
+        grammar: &CFGrammar,
+        states: &[BTreeSet<LR1Situation>],
+        gotos: &LR1Gotos,
+    ) -> Result<(), anyhow::Error> {
+        self.terminals = grammar.get_user_terminals();
+        self.rules = get_rules(grammar);
+        let indices = get_rule_indices(&self.rules);
+        self.items = states
+            .iter()
+            .map(|state| state.iter().map(ToString::to_string).collect())
+            .collect();
 
-            for state in prev_diff.iter() {
-                for symbol in all_symbols.iter() {
-                    let goto = Self::goto(grammar, state, *symbol);
+        for (state_mapped, state) in states.iter().enumerate() {
+            for situation in state.iter() {
+                if situation.pos < situation.size() {
+                    if grammar.is_terminal(&situation.nth(situation.pos)) {
+                        let letter = situation.nth(situation.pos);
+                        let goto_mapped = gotos[&(state_mapped, letter)];
+                        self.add_transition(state_mapped, letter, &LR1Action::Shift(goto_mapped))
+                            .with_context(|| Self::explain_conflict(state, letter))?;
+                    }
 
-                    if !goto.is_empty() && states.insert(goto.clone()) {
-                        curr_diff.insert(goto);
+                    if situation.rule.0 == START_RULE {
+                        self.start = state_mapped;
                     }
+
+                    continue;
+                }
+
+                if situation.rule.0 == START_RULE && situation.lookahead == END_TERMINAL {
+                    let letter = situation.lookahead;
+                    self.add_transition(state_mapped, letter, &LR1Action::Accept)
+                        .with_context(|| Self::explain_conflict(state, letter))?;
+                } else {
+                    let letter = situation.lookahead;
+                    let index = indices[&situation.rule];
+                    self.add_transition(state_mapped, letter, &LR1Action::Reduce(index))
+                        .with_context(|| Self::explain_conflict(state, letter))?;
                 }
             }
 
-            if curr_diff.len() == 0 {
-                break;
-            } else {
-                prev_diff = curr_diff;
+            for letter in grammar.non_terminals.iter() {
+                if let Some(goto_mapped) = gotos.get(&(state_mapped, *letter)) {
+                    let goto_mapped = *goto_mapped;
+                    self.add_transition(state_mapped, *letter, &LR1Action::Shift(goto_mapped))?;
+                }
             }
         }
 
-        states
+        Ok(())
     }
 
     fn add_transition(
@@ -280,33 +802,93 @@ impl LR1Parser {
         state: usize,
         letter: char,
         action: &LR1Action,
-    ) -> Result<(), anyhow::Error> {
-        let curr_entry = self
-            .transitions
-            .entry(state)
-            .or_default()
-            .entry(letter)
-            .or_default();
+    ) -> Result<(), Conflict> {
+        let terminals = &self.terminals;
+        let actions = self.transitions.entry(state).or_default();
+
+        // The wildcard action is taken on the letters without their own, so
+        // they must agree wherever both are present.
+        if terminals.contains(&letter) {
+            let other = actions.iter().find_map(|(other, other_action)| {
+                let shared = if letter == ANY_TERMINAL {
+                    terminals.contains(other) && *other != ANY_TERMINAL
+                } else {
+                    *other == ANY_TERMINAL
+                };
+                (shared && other_action != action).then_some(*other_action)
+            });
+
+            if let Some(other) = other {
+                return Err(Conflict {
+                    state,
+                    symbol: letter,
+                    actions: (other, *action),
+                });
+            }
+        }
+
+        let curr_entry = actions.entry(letter).or_default();
 
         if *curr_entry == LR1Action::NoAction || *curr_entry == *action {
             *curr_entry = *action;
             Ok(())
         } else {
-            bail!("The given grammar is not LR(1).")
+            Err(Conflict {
+                state,
+                symbol: letter,
+                actions: (*curr_entry, *action),
+            })
         }
     }
+
+    fn explain_conflict(state: &BTreeSet<LR1Situation>, letter: char) -> String {
+        let items: Vec<_> = state
+            .iter()
+            .filter(|situation| {
+                let symbol = if situation.pos < situation.size() {
+                    situation.nth(situation.pos)
+                } else {
+                    situation.lookahead
+                };
+                symbol == letter || (symbol == ANY_TERMINAL && letter != END_TERMINAL)
+            })
+            .map(|situation| situation.to_string())
+            .collect();
+        format!("Conflicting items: {}.", items.join("; "))
+    }
+}
+
+/// Rules of the grammar in a fixed order for the reduce actions to refer to.
+fn get_rules(grammar: &CFGrammar) -> Vec<CFRule> {
+    let rules: BTreeSet<_> = grammar
+        .rules
+        .flat_iter()
+        .map(|(rule_left, rule_right)| (*rule_left, rule_right.clone()))
+        .collect();
+    rules.into_iter().collect()
+}
+
+fn get_rule_indices(rules: &[CFRule]) -> HashMap<CFRule, usize> {
+    rules
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| (rule.clone(), index))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use glr::GLRParser;
+    use lalr::LALR1Parser;
+    use slr::SLRParser;
 
     #[test]
     fn lr1_unit_test_1() {
         let grammar = get_test_grammar();
         let mut parser = LR1Parser::new();
         parser.fit(&grammar).expect("Fit unsuccessful");
-        assert_eq!(Parser::predict(&mut parser, "cdd"), true);
+        assert!(Parser::predict(&mut parser, "cdd"));
     }
 
     #[test]
@@ -314,7 +896,442 @@ mod tests {
         let grammar = get_test_grammar();
         let mut parser = LR1Parser::new();
         parser.fit(&grammar).expect("Fit unsuccessful");
-        assert_eq!(Parser::predict(&mut parser, "ddd"), false);
+        assert!(!Parser::predict(&mut parser, "ddd"));
+    }
+
+    #[test]
+    fn lr1_predict_at_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.predict_at("ccdcd"), Ok(()));
+        assert_eq!(parser.predict_at("ddd"), Err(Some(2)));
+        assert_eq!(parser.predict_at("ccx"), Err(Some(2)));
+        assert_eq!(parser.predict_at("cd"), Err(Some(2)));
+        assert_eq!(parser.predict_at(""), Err(Some(0)));
+        assert_eq!(LR1Parser::new().predict_at("cd"), Err(None));
+
+        let mut parser = LR1Parser::with_limit(2);
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.predict_at("ccdcd"), Err(None));
+    }
+
+    #[test]
+    fn lr1_predict_position_test_1() {
+        // Lines of letters 'a' separated by newlines.
+        let grammar = CFGrammarBuilder::new()
+            .rule('S', "L")
+            .rule('S', "L\nS")
+            .rule('L', "aL")
+            .rule('L', "a")
+            .start('S')
+            .build()
+            .expect("Failed to build the grammar.");
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.predict_position("aa\na"), Ok(()));
+        assert_eq!(
+            parser.predict_position("aa\na\n\na"),
+            Err(Some(Position { line: 2, col: 0 }))
+        );
+        assert_eq!(parser.predict_at("aa\na\n\na"), Err(Some(5)));
+        assert_eq!(
+            parser.predict_position("aa\naa\n"),
+            Err(Some(Position { line: 2, col: 0 }))
+        );
+    }
+
+    #[test]
+    fn lr1_parse_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let node = |rule: (char, &str), children| ParseTree::Node {
+            rule: (rule.0, rule.1.to_string()),
+            children,
+        };
+        let tree = node(
+            ('S', "CC"),
+            vec![
+                node(
+                    ('C', "cC"),
+                    vec![
+                        ParseTree::Leaf('c'),
+                        node(('C', "d"), vec![ParseTree::Leaf('d')]),
+                    ],
+                ),
+                node(('C', "d"), vec![ParseTree::Leaf('d')]),
+            ],
+        );
+        assert_eq!(parser.parse("cdd"), Ok(tree));
+        assert_eq!(
+            parser.parse("ddd"),
+            Err(ParseError::UnexpectedSymbol { pos: 2, found: 'd' })
+        );
+        assert_eq!(parser.parse("cd"), Err(ParseError::UnexpectedEnd));
+        assert_eq!(LR1Parser::new().parse("cdd"), Err(ParseError::NotFitted));
+    }
+
+    #[test]
+    fn lr1_parse_test_2() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        let mut lalr = LALR1Parser::new();
+        let mut slr = SLRParser::new();
+        let mut glr = GLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        lalr.fit(&grammar).expect("Fit unsuccessful");
+        slr.fit(&grammar).expect("Fit unsuccessful");
+        glr.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["cdd", "dccd", "ccdcd"] {
+            let tree = parser.parse(word).expect("Parse unsuccessful");
+            assert_eq!(lalr.parse(word), Ok(tree.clone()), "{word}");
+            assert_eq!(slr.parse(word), Ok(tree.clone()), "{word}");
+            assert_eq!(glr.parse(word), Ok(tree), "{word}");
+        }
+    }
+
+    #[test]
+    fn lr1_tokens_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(parser.predict_tokens(&['c', 'd', 'd']));
+        assert!(!parser.predict_tokens(&['d']));
+        assert_eq!(
+            parser.parse_tokens(&['c', 'd', 'd']).ok(),
+            Parser::parse(&mut parser, "cdd").ok()
+        );
+    }
+
+    #[test]
+    fn lr1_predict_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let get_size =
+            |parser: &LR1Parser| parser.transitions.values().map(HashMap::len).sum::<usize>();
+        let size = get_size(&parser);
+
+        for _ in 0..2 {
+            assert!(Parser::predict(&mut parser, "cdd"));
+            assert!(!Parser::predict(&mut parser, "dcx"));
+            assert!(!Parser::predict(&mut parser, "c"));
+            assert_eq!(get_size(&parser), size);
+        }
+    }
+
+    #[test]
+    fn lr1_nullable_test_1() {
+        let grammar = CFGrammar::from_str("SXYZ\nbyz\nS->Xb\nX->YZ\nY->y\nY->\nZ->z\nZ->\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["b", "yb", "zb", "yzb"] {
+            assert!(Parser::predict(&mut parser, word));
+        }
+
+        assert!(!Parser::predict(&mut parser, "zyb"));
+    }
+
+    #[test]
+    fn lr1_longest_match_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        assert_eq!(parser.longest_match("dd"), None);
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.longest_match("dd"), Some(2));
+        assert_eq!(parser.longest_match("dcdd"), Some(3));
+        assert_eq!(parser.longest_match("cdcdcd"), Some(4));
+        assert_eq!(parser.longest_match("ddxd"), Some(2));
+        assert_eq!(parser.longest_match("dCd"), None);
+        assert_eq!(parser.longest_match("d"), None);
+        assert_eq!(parser.longest_match(""), None);
+
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.longest_match(""), Some(0));
+        assert_eq!(parser.longest_match("aabbb"), Some(4));
+        assert_eq!(parser.longest_match("aab"), Some(0));
+        assert_eq!(parser.longest_match("b"), Some(0));
+    }
+
+    #[test]
+    fn lr1_match_mode_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        assert!(!parser.matches("", MatchMode::Prefix));
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let mut lalr = LALR1Parser::new();
+        lalr.fit(&grammar).expect("Fit unsuccessful");
+        let mut slr = SLRParser::new();
+        slr.fit(&grammar).expect("Fit unsuccessful");
+
+        for (word, exact, prefix) in [
+            ("cdd", true, true),
+            ("ccd", false, true),
+            ("", false, true),
+            ("dcc", false, true),
+            ("ddd", false, false),
+            ("dS", false, false),
+        ] {
+            assert_eq!(parser.matches(word, MatchMode::Exact), exact, "{word}");
+            assert_eq!(parser.matches(word, MatchMode::Prefix), prefix, "{word}");
+            assert_eq!(lalr.matches(word, MatchMode::Prefix), prefix, "{word}");
+            assert_eq!(slr.matches(word, MatchMode::Prefix), prefix, "{word}");
+        }
+    }
+
+    #[test]
+    fn lr1_refit_test_1() {
+        let other =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::with_limit(100);
+        let mut lalr = LALR1Parser::new();
+        let mut slr = SLRParser::new();
+
+        for grammar in [get_test_grammar(), other] {
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            lalr.fit(&grammar).expect("Fit unsuccessful");
+            slr.fit(&grammar).expect("Fit unsuccessful");
+        }
+
+        assert!(Parser::predict(&mut parser, "aabb"));
+        assert!(!Parser::predict(&mut parser, "cdd"));
+        assert!(Parser::predict(&mut lalr, "ab"));
+        assert!(!Parser::predict(&mut lalr, "dd"));
+        assert!(Parser::predict(&mut slr, ""));
+        assert!(!Parser::predict(&mut slr, "cdd"));
+        assert_eq!(parser.limit, Some(100));
+    }
+
+    #[test]
+    fn lr1_rightmost_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let rule = |rule_left, rule_right: &str| (rule_left, rule_right.to_string());
+        assert_eq!(
+            parser.rightmost_derivation("cdd"),
+            Some(vec![
+                rule('S', "CC"),
+                rule('C', "d"),
+                rule('C', "cC"),
+                rule('C', "d"),
+            ])
+        );
+        let mut reduces: Vec<_> = parser
+            .trace("cdd")
+            .into_iter()
+            .filter_map(|(_, _, action)| match action {
+                LR1Action::Reduce(index) => Some(parser.rules()[index].clone()),
+                _ => None,
+            })
+            .collect();
+        reduces.reverse();
+        assert_eq!(Some(reduces), parser.rightmost_derivation("cdd"));
+        assert_eq!(parser.rightmost_derivation("cd"), None);
+        let mut lalr = LALR1Parser::new();
+        lalr.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(
+            lalr.rightmost_derivation("dcd"),
+            parser.rightmost_derivation("dcd")
+        );
+    }
+
+    #[test]
+    fn lr1_trace_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let trace = parser.trace("dd");
+        let actions: Vec<_> = trace.iter().map(|step| step.2).collect();
+        let shift = |state| parser.transitions[&state][&'d'];
+        assert_eq!(trace[0], (parser.start, 'd', shift(parser.start)));
+        let reduce = |rule_left, rule_right: &str| {
+            let rule = (rule_left, rule_right.to_string());
+            LR1Action::Reduce(
+                parser
+                    .rules()
+                    .iter()
+                    .position(|other| *other == rule)
+                    .unwrap(),
+            )
+        };
+        let (to_c, to_s) = (reduce('C', "d"), reduce('S', "CC"));
+        assert!(matches!(
+            actions[..],
+            [
+                LR1Action::Shift(_),
+                action_1,
+                LR1Action::Shift(_),
+                action_2,
+                action_3,
+                LR1Action::Accept,
+            ] if action_1 == to_c && action_2 == to_c && action_3 == to_s
+        ));
+        let lookaheads: String = trace.iter().map(|step| step.1).collect();
+        assert_eq!(
+            lookaheads,
+            format!("ddd{END_TERMINAL}{END_TERMINAL}{END_TERMINAL}")
+        );
+        let trace = parser.trace("dc");
+        assert_eq!(trace.last().unwrap().2, LR1Action::NoAction);
+        assert_eq!(trace.last().unwrap().1, END_TERMINAL);
+    }
+
+    #[cfg(feature = "lr-internals")]
+    #[test]
+    fn lr1_internals_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let (states, gotos) = LR1Parser::states(&grammar).expect("Failed to build the states.");
+        assert_eq!(states.len(), parser.num_states());
+        assert!(states[0]
+            .iter()
+            .any(|situation| situation.rule().0 == START_RULE && situation.pos() == 0));
+        assert!(states[0]
+            .iter()
+            .all(|situation| situation.lookahead() != EPS_TERMINAL));
+
+        for ((state, symbol), next) in gotos {
+            assert_eq!(
+                parser.transitions()[&state][&symbol],
+                LR1Action::Shift(next)
+            );
+        }
+
+        assert_eq!(parser.start_state(), 0);
+    }
+
+    #[test]
+    fn lr1_stats_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        assert_eq!(parser.num_states(), 0);
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.num_states(), 10);
+        assert_eq!(parser.num_shifts(), 13);
+        assert_eq!(parser.num_reduces(), 7);
+        assert_eq!(parser.num_transitions(), 21);
+        let mut lalr = LALR1Parser::new();
+        lalr.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(lalr.num_states(), 7);
+        let mut slr = SLRParser::new();
+        slr.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(slr.num_states(), 7);
+        assert_eq!(slr.num_transitions(), lalr.num_transitions());
+    }
+
+    #[test]
+    fn lr1_minimize_test_1() {
+        let grammar = CFGrammar::from_str("SA\nabc\nS->aA\nS->bA\nA->c\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let mut minimized = parser.clone();
+        minimized.minimize();
+        // The states after `aA` and `bA` reduce different rules, so they are kept.
+        assert_eq!(parser.num_states(), 7);
+        assert_eq!(minimized.num_states(), 7);
+        let words = ["ac", "bc", "", "a", "c", "abc", "acc", "bcx"];
+
+        for word in words {
+            assert_eq!(
+                Parser::parse(&mut minimized, word),
+                Parser::parse(&mut parser, word),
+                "{word}"
+            );
+        }
+
+        let mut parser = LR1Parser::new();
+        parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+        let mut minimized = parser.clone();
+        minimized.minimize();
+        assert_eq!(minimized.num_states(), parser.num_states());
+
+        // Copy a state and send one of the shifts into it to get a state to merge.
+        let (state, letter, next) = parser
+            .transitions
+            .iter()
+            .flat_map(|(state, actions)| {
+                actions.iter().filter_map(|(letter, action)| match action {
+                    LR1Action::Shift(next) => Some((*state, *letter, *next)),
+                    _ => None,
+                })
+            })
+            .min()
+            .unwrap();
+        let copy = parser.num_states();
+        let mut duplicated = parser.clone();
+        let actions = duplicated.transitions[&next].clone();
+        duplicated.transitions.insert(copy, actions);
+        duplicated.items.push(duplicated.items[next].clone());
+        duplicated
+            .transitions
+            .get_mut(&state)
+            .unwrap()
+            .insert(letter, LR1Action::Shift(copy));
+        let mut minimized = duplicated.clone();
+        minimized.minimize();
+        assert_eq!(duplicated.num_states(), parser.num_states() + 1);
+        assert!(minimized.num_states() < duplicated.num_states());
+        assert_eq!(minimized.num_states(), parser.num_states());
+        assert_eq!(minimized.num_transitions(), parser.num_transitions());
+
+        for word in [
+            "cdd", "dccd", "ddd", "", "cc", "dcdc", "cccdcd", "ccdd", "dd",
+        ] {
+            let expected = Parser::parse(&mut parser, word);
+            assert_eq!(Parser::parse(&mut duplicated, word), expected, "{word}");
+            assert_eq!(Parser::parse(&mut minimized, word), expected, "{word}");
+        }
+    }
+
+    #[test]
+    fn lr1_dot_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let dot = parser.to_dot();
+        assert!(dot.starts_with("digraph LR {"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(dot.matches(" [label=").count(), 10 + 13);
+        assert!(dot.contains("S -> CC·, \\\\u{2}"));
+        assert!(dot.contains("accept"));
+        assert!(dot.contains("reduce S -> CC"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn lr1_serde_test_1() {
+        let mut parser = LR1Parser::with_limit(100);
+        parser
+            .fit(&crate::get_test_grammar())
+            .expect("Fit unsuccessful");
+        let json = serde_json::to_string(&parser).expect("Failed to serialize the parser.");
+        let mut loaded: LR1Parser =
+            serde_json::from_str(&json).expect("Failed to deserialize the parser.");
+        assert_eq!(loaded.transitions, parser.transitions);
+        assert_eq!(loaded.start, parser.start);
+        assert_eq!(loaded.terminals, parser.terminals);
+        assert_eq!(loaded.rules, parser.rules);
+        assert_eq!(loaded.items, parser.items);
+        assert_eq!(loaded.limit, Some(100));
+
+        for word in ["a", "(a+a)*a", "a+a*a", "", "a+", "(a", "a*(a)"] {
+            assert_eq!(
+                Parser::predict(&mut loaded, word),
+                Parser::predict(&mut parser, word),
+                "{word}"
+            );
+        }
+
+        assert!(serde_json::from_str::<LR1Parser>("[]").is_err());
     }
 
     #[test]
@@ -322,10 +1339,359 @@ mod tests {
         let grammar =
             CFGrammar::from_str("S\na\nS->Sa\nS->a\nS->\nS").expect("Failed to parse the grammar.");
         let mut parser = LR1Parser::new();
-        assert_eq!(parser.fit(&grammar).is_err(), true);
+        assert!(parser.fit(&grammar).is_err());
+    }
+
+    #[test]
+    fn lr1_should_fail_2() {
+        let grammar = CFGrammar::from_str("SAB\nac\nS->Ac\nS->Bc\nA->a\nB->a\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::new();
+        let message = format!("{:#}", parser.fit(&grammar).unwrap_err());
+        assert!(message.starts_with("The given grammar is not LR(1)."));
+        assert!(message.contains("Reduce/reduce conflict"));
+        assert!(message.contains("A -> a·, c"));
+        assert!(message.contains("B -> a·, c"));
+    }
+
+    #[test]
+    fn lr1_should_fail_4() {
+        let mut grammar =
+            CFGrammar::from_str("S\na\nS->a\nS").expect("Failed to parse the grammar.");
+        grammar.rules.insert(START_RULE, "S".to_string());
+
+        for parser in [
+            &mut LR1Parser::new() as &mut dyn Parser,
+            &mut LALR1Parser::new(),
+            &mut SLRParser::new(),
+            &mut GLRParser::new(),
+        ] {
+            assert!(parser.fit(&grammar).is_err());
+        }
+    }
+
+    #[test]
+    fn lr1_limit_test_1() {
+        let grammar = get_test_grammar();
+        let word = "c".repeat(20) + "dd";
+
+        for (parser, mut limited) in [
+            (
+                &mut LR1Parser::new() as &mut dyn Parser,
+                Box::new(LR1Parser::with_limit(20)) as Box<dyn Parser>,
+            ),
+            (
+                &mut LALR1Parser::new(),
+                Box::new(LALR1Parser::with_limit(20)),
+            ),
+            (&mut SLRParser::new(), Box::new(SLRParser::with_limit(20))),
+        ] {
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            limited.fit(&grammar).expect("Fit unsuccessful");
+            assert!(parser.predict(&word));
+            assert!(limited.predict("cdd"));
+            assert_eq!(limited.parse(&word), Err(ParseError::LimitExceeded));
+        }
+    }
+
+    #[test]
+    fn lr1_wildcard_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->.S|.\nS").expect("Failed to parse the grammar.");
+        let mut parsers: Vec<Box<dyn Parser>> = vec![
+            Box::new(LR1Parser::new()),
+            Box::new(lalr::LALR1Parser::new()),
+            Box::new(slr::SLRParser::new()),
+            Box::new(glr::GLRParser::new()),
+        ];
+
+        for parser in parsers.iter_mut() {
+            parser.fit(&grammar).expect("Fit unsuccessful");
+
+            for word in ["a", "S", "xy.", "abba"] {
+                assert!(parser.predict(word), "{word}");
+            }
+
+            assert!(!parser.predict(""));
+        }
+
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert_eq!(parser.longest_match("xyz"), Some(3));
+        assert!(parser.matches("S", MatchMode::Prefix));
+
+        // Both the letter and the wildcard match 'a'.
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->ab|.a\nS").expect("Failed to parse the grammar.");
+        assert!(LR1Parser::new().fit(&grammar).is_err());
+        let mut parser = glr::GLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "ab"));
+        assert!(Parser::predict(&mut parser, "aa"));
+        assert!(Parser::predict(&mut parser, "ba"));
+        assert!(!Parser::predict(&mut parser, "bb"));
+    }
+
+    #[test]
+    fn parsing_class_test_1() {
+        let grammars = [
+            ("S\nabc\nS->aSb\nS->c\nS", ParsingClass::LR0),
+            ("SC\ncd\nS->CC\nC->cC\nC->d\nS", ParsingClass::LR0),
+            (
+                "ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE",
+                ParsingClass::SLR1,
+            ),
+            ("S\nab\nS->aSb\nS->\nS", ParsingClass::SLR1),
+            (
+                "SLR\n=*i\nS->L=R\nS->R\nL->*R\nL->i\nR->L\nS",
+                ParsingClass::LALR1,
+            ),
+            (
+                "SAB\nabcde\nS->aAd\nS->bBd\nS->aBe\nS->bAe\nA->c\nB->c\nS",
+                ParsingClass::LR1,
+            ),
+            ("S\n+a\nS->S+S\nS->a\nS", ParsingClass::NotLR1),
+        ];
+
+        for (grammar, class) in grammars {
+            let grammar = CFGrammar::from_str(grammar).expect("Failed to parse the grammar.");
+            assert_eq!(grammar.parsing_class(), class);
+        }
+
+        assert!(ParsingClass::LR0 < ParsingClass::NotLR1);
+    }
+
+    #[test]
+    fn lr1_check_test_1() {
+        assert_eq!(LR1Parser::check_lr1(&get_test_grammar()).unwrap(), None);
+        let grammar =
+            CFGrammar::from_str("S\n+a\nS->S+S\nS->a\nS").expect("Failed to parse the grammar.");
+        let conflict = LR1Parser::check_lr1(&grammar).unwrap().unwrap();
+        assert_eq!(conflict.symbol, '+');
+        let index = get_rules(&grammar)
+            .iter()
+            .position(|rule| *rule == ('S', "S+S".to_string()))
+            .unwrap();
+        assert!(matches!(
+            conflict.actions,
+            (LR1Action::Shift(_), LR1Action::Reduce(reduced))
+                | (LR1Action::Reduce(reduced), LR1Action::Shift(_)) if reduced == index
+        ));
+        let err = LR1Parser::new().fit(&grammar).unwrap_err();
+        assert_eq!(err.downcast_ref::<Conflict>(), Some(&conflict));
+        let grammar = CFGrammar::from_str("SAB\nac\nS->Ac\nS->Bc\nA->a\nB->a\nS")
+            .expect("Failed to parse the grammar.");
+        let conflict = LR1Parser::check_lr1(&grammar).unwrap().unwrap();
+        assert_eq!(conflict.symbol, 'c');
+        assert!(conflict.to_string().starts_with("Reduce/reduce conflict"));
+
+        // Two start rules, there is no automaton to check.
+        let mut grammar =
+            CFGrammar::from_str("S\na\nS->a\nS").expect("Failed to parse the grammar.");
+        grammar.rules.insert(START_RULE, "S".to_string());
+        assert!(LR1Parser::check_lr1(&grammar).is_err());
+        assert_eq!(grammar.parsing_class(), ParsingClass::NotLR1);
+    }
+
+    #[test]
+    fn lr1_epsilon_test_1() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->a|\nB->b|\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(parser
+            .transitions
+            .values()
+            .all(|actions| !actions.contains_key(&EPS_TERMINAL)));
+        let mut parser = GLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in ["", "a", "b", "ab"] {
+            assert!(parser.predict(word));
+        }
+
+        assert!(!parser.predict("\u{3}"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "earley", feature = "cyk"))]
+    fn lr1_empty_word_test_1() {
+        use crate::cyk::CYKParser;
+        use crate::earley::EarleyParser;
+
+        for grammar in [
+            "S\na\nS->aS\nS->\nS",
+            "S\na\nS->aS\nS->a\nS",
+            "SAB\nab\nS->AB\nA->aA|\nB->b|\nS",
+            "SAB\nab\nS->AB\nA->aA|\nB->b\nS",
+        ] {
+            let grammar = CFGrammar::from_str(grammar).expect("Failed to parse the grammar.");
+            let cnf = grammar.to_cnf();
+            let expected = grammar.accepts_empty();
+
+            for (parser, grammar) in [
+                (&mut EarleyParser::new() as &mut dyn Parser, &grammar),
+                (&mut LR1Parser::new(), &grammar),
+                (&mut LALR1Parser::new(), &grammar),
+                (&mut SLRParser::new(), &grammar),
+                (&mut GLRParser::new(), &grammar),
+                (&mut CYKParser::new(), &cnf),
+            ] {
+                parser.fit(grammar).expect("Fit unsuccessful");
+                assert_eq!(parser.predict(""), expected, "{grammar}");
+            }
+
+            for parser in [
+                &mut EarleyParser::with_limit(0) as &mut dyn Parser,
+                &mut LR1Parser::with_limit(0),
+            ] {
+                parser.fit(&grammar).expect("Fit unsuccessful");
+                assert_eq!(parser.parse(""), Err(ParseError::LimitExceeded));
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "earley", feature = "cyk"))]
+    fn lr1_reset_test_1() {
+        use crate::cyk::CYKParser;
+        use crate::earley::EarleyParser;
+
+        let grammar = get_test_grammar();
+        let parsers: Vec<(Box<dyn Parser>, CFGrammar)> = vec![
+            (Box::new(EarleyParser::with_limit(100)), grammar.clone()),
+            (Box::new(LR1Parser::with_limit(100)), grammar.clone()),
+            (Box::new(LALR1Parser::new()), grammar.clone()),
+            (Box::new(SLRParser::new()), grammar.clone()),
+            (Box::new(GLRParser::new()), grammar.clone()),
+            (Box::new(CYKParser::new()), grammar.to_cnf()),
+        ];
+
+        for (mut parser, grammar) in parsers {
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            assert!(parser.predict("cdd"));
+            parser.reset();
+            assert!(!parser.predict("cdd"));
+            assert_eq!(parser.parse("cdd"), Err(ParseError::NotFitted));
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            assert!(parser.predict("cdd"));
+        }
+
+        let mut parser = LR1Parser::with_limit(100);
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        parser.reset();
+        assert_eq!(parser.limit, Some(100));
+        assert_eq!(parser.num_states(), 0);
+    }
+
+    #[test]
+    fn lr1_unicode_test_1() {
+        let grammar = CFGrammar::from_str("Sλ\nαβ😀\nS->αSβ|λ\nλ->😀|\nS")
+            .expect("Failed to parse the grammar.");
+
+        for parser in [
+            &mut LR1Parser::new() as &mut dyn Parser,
+            &mut LALR1Parser::new(),
+            &mut SLRParser::new(),
+            &mut GLRParser::new(),
+        ] {
+            parser.fit(&grammar).expect("Fit unsuccessful");
+
+            for word in ["", "😀", "αβ", "αα😀ββ"] {
+                assert!(parser.predict(word));
+            }
+
+            for word in ["α", "αβ😀", "αλβ"] {
+                assert!(!parser.predict(word));
+            }
+
+            assert_eq!(
+                parser.parse("α😀😀"),
+                Err(ParseError::UnexpectedSymbol {
+                    pos: 2,
+                    found: '😀'
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn lr1_unicode_test_2() {
+        let grammar = CFGrammar::from_str("Sλ\nαβ😀\nS->αSβ|λ\nλ->😀|\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let reduces: Vec<_> = parser
+            .trace("α😀β")
+            .into_iter()
+            .filter_map(|step| match step.2 {
+                LR1Action::Reduce(index) => Some(parser.rules()[index].clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            reduces,
+            vec![
+                ('λ', "😀".to_string()),
+                ('S', "λ".to_string()),
+                ('S', "αSβ".to_string()),
+            ]
+        );
+        let tree = parser.parse("α😀β").expect("Parse unsuccessful");
+        assert_eq!(tree, parser.parse_tokens(&['α', '😀', 'β']).unwrap());
+        assert!(matches!(tree, ParseTree::Node { ref children, .. } if children.len() == 3));
+    }
+
+    #[test]
+    fn lr1_should_fail_5() {
+        let grammar = get_test_grammar();
+
+        for parser in [
+            &mut LR1Parser::new() as &mut dyn Parser,
+            &mut LALR1Parser::new(),
+            &mut SLRParser::new(),
+            &mut GLRParser::new(),
+        ] {
+            parser.fit(&grammar).expect("Fit unsuccessful");
+            assert!(!parser.predict("C"));
+            assert!(!parser.predict("cC"));
+            assert_eq!(
+                parser.parse("cdx"),
+                Err(ParseError::UnexpectedSymbol { pos: 2, found: 'x' })
+            );
+        }
+    }
+
+    #[test]
+    fn lr1_should_fail_3() {
+        let grammar =
+            CFGrammar::from_str("S\n+a\nS->S+S\nS->a\nS").expect("Failed to parse the grammar.");
+        let mut parser = LR1Parser::new();
+        let message = format!("{:#}", parser.fit(&grammar).unwrap_err());
+        assert!(message.contains("Shift/reduce conflict"));
+        assert!(message.contains("on '+'"));
+    }
+
+    #[test]
+    fn lr1_should_fail_6() {
+        let grammar =
+            CFGrammar::from_str("SE\na+\nS->E\nE->E+E|a\nS").expect("Failed to parse the grammar.");
+
+        for parser in [
+            &mut LR1Parser::new() as &mut dyn Parser,
+            &mut LALR1Parser::new(),
+            &mut SLRParser::new(),
+        ] {
+            assert!(parser.fit(&grammar).is_err());
+            assert_eq!(parser.parse("a+a"), Err(ParseError::NotFitted));
+
+            parser.fit(&get_test_grammar()).expect("Fit unsuccessful");
+            assert!(parser.fit(&grammar).is_err());
+            assert_eq!(parser.parse("a"), Err(ParseError::NotFitted));
+        }
     }
 
-    fn get_test_grammar() -> CFGrammar {
+    pub(super) fn get_test_grammar() -> CFGrammar {
         let terminals = HashSet::from(['c', 'd']);
         let non_terminals = HashSet::from(['S', 'C']);
         let mut rules = MultiMap::new();
@@ -0,0 +1,323 @@
+use anyhow::Context;
+
+use super::*;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct LR0Situation {
+    rule: CFRule,
+    pos: usize,
+    /// Number of symbols in the right part of the rule.
+    size: usize,
+}
+
+impl LR0Situation {
+    fn new(rule: &CFRule, pos: usize) -> Self {
+        Self {
+            rule: rule.clone(),
+            pos,
+            size: rule.1.chars().count(),
+        }
+    }
+
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn nth(&self, pos: usize) -> char {
+        self.rule.1.chars().nth(pos).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SLRParser {
+    /// Action table in the LR(1) format.
+    parser: LR1Parser,
+}
+
+impl Parser for SLRParser {
+    fn fit(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        self.parser.reset();
+        let mut parser = self.clone();
+        parser
+            .build_table(grammar)
+            .with_context(|| "The given grammar is not SLR(1).")?;
+        *self = parser;
+        Ok(())
+    }
+
+    fn parse(&mut self, word: &str) -> Result<ParseTree, ParseError> {
+        self.parser.parse(word)
+    }
+
+    fn reset(&mut self) {
+        self.parser.reset();
+    }
+}
+
+#[cfg(feature = "lr-internals")]
+impl SLRParser {
+    /// Table in the format of the canonical parser.
+    pub fn table(&self) -> &LR1Parser {
+        &self.parser
+    }
+}
+
+impl SLRParser {
+    pub fn new() -> Self {
+        Self {
+            parser: LR1Parser::new(),
+        }
+    }
+
+    /// Parser giving up on words that take more than `limit` moves.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            parser: LR1Parser::with_limit(limit),
+        }
+    }
+
+    /// Check if the already tokenized word is in the language.
+    pub fn predict_tokens(&self, tokens: &[char]) -> bool {
+        self.parser.predict_tokens(tokens)
+    }
+
+    /// Check the word either as a whole or as a prefix.
+    pub fn matches(&self, word: &str, mode: MatchMode) -> bool {
+        self.parser.matches(word, mode)
+    }
+
+    /// Length of the longest prefix of the word in the language, if there is one.
+    pub fn longest_match(&self, word: &str) -> Option<usize> {
+        self.parser.longest_match(word)
+    }
+
+    /// Rules applied in the rightmost derivation of the word from the start.
+    pub fn rightmost_derivation(&self, word: &str) -> Option<Vec<CFRule>> {
+        self.parser.rightmost_derivation(word)
+    }
+
+    /// Merge the states with the same actions on every lookahead.
+    pub fn minimize(&mut self) {
+        self.parser.minimize()
+    }
+
+    pub fn num_states(&self) -> usize {
+        self.parser.num_states()
+    }
+
+    pub fn num_transitions(&self) -> usize {
+        self.parser.num_transitions()
+    }
+
+    pub fn num_shifts(&self) -> usize {
+        self.parser.num_shifts()
+    }
+
+    pub fn num_reduces(&self) -> usize {
+        self.parser.num_reduces()
+    }
+
+    fn build_table(&mut self, grammar: &CFGrammar) -> Result<(), anyhow::Error> {
+        let (states, gotos) = Self::get_states(grammar)?;
+        let follow_sets = grammar.get_follow_sets();
+        self.parser.reset();
+        self.parser.terminals = grammar.get_user_terminals();
+        self.parser.rules = get_rules(grammar);
+        let indices = get_rule_indices(&self.parser.rules);
+
+        for (state_mapped, state) in states.iter().enumerate() {
+            for situation in state.iter() {
+                if situation.pos < situation.size() {
+                    if grammar.is_terminal(&situation.nth(situation.pos)) {
+                        let letter = situation.nth(situation.pos);
+                        let goto_mapped = gotos[&(state_mapped, letter)];
+                        self.parser.add_transition(
+                            state_mapped,
+                            letter,
+                            &LR1Action::Shift(goto_mapped),
+                        )?;
+                    }
+
+                    if situation.rule.0 == START_RULE {
+                        self.parser.start = state_mapped;
+                    }
+
+                    continue;
+                }
+
+                if situation.rule.0 == START_RULE {
+                    self.parser
+                        .add_transition(state_mapped, END_TERMINAL, &LR1Action::Accept)?;
+                    continue;
+                }
+
+                for letter in follow_sets[&situation.rule.0].iter().copied() {
+                    let index = indices[&situation.rule];
+                    self.parser
+                        .add_transition(state_mapped, letter, &LR1Action::Reduce(index))?;
+                }
+            }
+
+            for letter in grammar.non_terminals.iter() {
+                if let Some(goto_mapped) = gotos.get(&(state_mapped, *letter)) {
+                    let goto_mapped = *goto_mapped;
+                    self.parser.add_transition(
+                        state_mapped,
+                        *letter,
+                        &LR1Action::Shift(goto_mapped),
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that no state of the LR(0) automaton has a finished item along with
+    /// another finished item or a shift of a terminal.
+    pub(super) fn is_lr0(grammar: &CFGrammar) -> bool {
+        let Ok((states, _)) = Self::get_states(grammar) else {
+            return false;
+        };
+
+        states.iter().all(|state| {
+            let mut reduces = 0;
+            let mut shifts = 0;
+
+            for situation in state.iter() {
+                if situation.pos >= situation.size() {
+                    // Accepting behaves as a shift of the end of the word.
+                    if situation.rule.0 == START_RULE {
+                        shifts += 1;
+                    } else {
+                        reduces += 1;
+                    }
+                } else if !grammar.is_non_terminal(&situation.nth(situation.pos)) {
+                    shifts += 1;
+                }
+            }
+
+            reduces == 0 || (reduces == 1 && shifts == 0)
+        })
+    }
+
+    fn closure(grammar: &CFGrammar, state: &BTreeSet<LR0Situation>) -> BTreeSet<LR0Situation> {
+        let mut new_state = state.clone();
+        let mut queue: VecDeque<_> = state.iter().cloned().collect();
+
+        while let Some(situation) = queue.pop_front() {
+            if situation.pos >= situation.size() {
+                continue;
+            }
+
+            let rule_left = situation.nth(situation.pos);
+
+            for rule_right in grammar.rules.get_vec(&rule_left).into_iter().flatten() {
+                let new_situation = LR0Situation::new(&(rule_left, rule_right.clone()), 0);
+
+                if !new_state.contains(&new_situation) {
+                    new_state.insert(new_situation.clone());
+                    queue.push_back(new_situation);
+                }
+            }
+        }
+
+        new_state
+    }
+
+    fn goto(
+        grammar: &CFGrammar,
+        state: &BTreeSet<LR0Situation>,
+        symbol: char,
+    ) -> BTreeSet<LR0Situation> {
+        let new_state = state
+            .iter()
+            .filter_map(|situation| {
+                if situation.pos >= situation.size() || situation.nth(situation.pos) != symbol {
+                    None
+                } else {
+                    Some(LR0Situation::new(&situation.rule, situation.pos + 1))
+                }
+            })
+            .collect();
+        Self::closure(grammar, &new_state)
+    }
+
+    /// States of the LR(0) automaton with the start one first, and the
+    /// transitions between them, each computed once.
+    fn get_states(
+        grammar: &CFGrammar,
+    ) -> Result<(Vec<BTreeSet<LR0Situation>>, LR1Gotos), anyhow::Error> {
+        let mut states = vec![Self::closure(
+            grammar,
+            &BTreeSet::<_>::from([LR0Situation::new(&grammar.get_start_rule()?, 0)]),
+        )];
+        let mut mapping = HashMap::from([(states[0].clone(), 0)]);
+        let mut gotos = LR1Gotos::new();
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(curr) = queue.pop_front() {
+            let symbols: BTreeSet<_> = states[curr]
+                .iter()
+                .filter(|situation| situation.pos < situation.size())
+                .map(|situation| situation.nth(situation.pos))
+                .collect();
+
+            for symbol in symbols {
+                let goto = Self::goto(grammar, &states[curr], symbol);
+                let next = match mapping.get(&goto) {
+                    Some(next) => *next,
+                    None => {
+                        mapping.insert(goto.clone(), states.len());
+                        queue.push_back(states.len());
+                        states.push(goto);
+                        states.len() - 1
+                    }
+                };
+                gotos.insert((curr, symbol), next);
+            }
+        }
+
+        Ok((states, gotos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slr_unit_test_1() {
+        let grammar = get_test_grammar();
+        let mut parser = SLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "(a+a)*a"));
+        assert!(!Parser::predict(&mut parser, "(a+a*a())"));
+    }
+
+    #[test]
+    fn slr_unit_test_2() {
+        let grammar = CFGrammar::from_str("SC\ncd\nS->CC\nC->cC\nC->d\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(
+            SLRParser::get_states(&grammar)
+                .expect("Failed to build the states.")
+                .0
+                .len(),
+            7
+        );
+        let mut parser = SLRParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        assert!(Parser::predict(&mut parser, "cdd"));
+        assert!(!Parser::predict(&mut parser, "ddd"));
+    }
+
+    #[test]
+    fn slr_should_fail_1() {
+        let grammar = CFGrammar::from_str("SLR\n=*a\nS->L=R\nS->R\nL->*R\nL->a\nR->L\nS")
+            .expect("Failed to parse the grammar.");
+        let mut parser = SLRParser::new();
+        assert!(parser.fit(&grammar).is_err());
+    }
+}
@@ -1,10 +1,92 @@
-use langram::lr1::LR1Parser;
-use langram::{CFGrammar, FromStr, Parser};
-
-fn main() {
-    let grammar =
-        CFGrammar::from_str("S\na\nS->Sa\nS->a\nS->\nS").expect("Failed to parse the grammar.");
-    let mut parser = LR1Parser::new();
-    parser.fit(&grammar).expect("Failed to fit the parser");
-    println!("{}", parser.predict("cdd"));
+use std::process::ExitCode;
+
+use anyhow::Context;
+use langram::{bail, make_parser, CFGrammar, FromStr};
+
+const USAGE: &str = "Usage: langram [--parser KIND] GRAMMAR_FILE WORD...
+
+Check if the words are in the language of the grammar, one verdict per line.
+KIND is one of cyk, earley, ll1, lr1, lalr1, slr1 and glr, earley by default.
+Grammars are converted to the Chomsky normal form for cyk.
+
+The exit code is 0 if every word is accepted, 1 if some are rejected and 2
+on errors.";
+
+struct Args {
+    kind: String,
+    path: String,
+    words: Vec<String>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, anyhow::Error> {
+    let mut kind = "earley".to_string();
+    let mut positional = Vec::new();
+    let mut args = args.peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--parser" | "-p" => match args.next() {
+                Some(value) => kind = value,
+                None => bail!("The parser kind is missing."),
+            },
+            "--" => positional.extend(args.by_ref()),
+            _ => positional.push(arg),
+        }
+    }
+
+    if positional.len() < 2 {
+        bail!("The grammar file and at least one word are required.");
+    }
+
+    let path = positional.remove(0);
+    Ok(Args {
+        kind,
+        path,
+        words: positional,
+    })
+}
+
+fn run(args: &Args) -> Result<bool, anyhow::Error> {
+    let input = std::fs::read_to_string(&args.path)
+        .with_context(|| format!("Failed to read {:?}.", args.path))?;
+    let mut grammar =
+        CFGrammar::from_str(&input).with_context(|| "Failed to parse the grammar.")?;
+
+    if args.kind == "cyk" {
+        grammar = grammar.to_cnf();
+    }
+
+    let mut parser = make_parser(&args.kind)?;
+    parser.fit(&grammar)?;
+    let mut all_accepted = true;
+
+    for word in args.words.iter() {
+        match parser.parse(word) {
+            Ok(_) => println!("{word:?}: accepted"),
+            Err(err) => {
+                println!("{word:?}: rejected, {err}");
+                all_accepted = false;
+            }
+        }
+    }
+
+    Ok(all_accepted)
+}
+
+fn main() -> ExitCode {
+    if std::env::args().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+
+    let result = parse_args(std::env::args().skip(1)).and_then(|args| run(&args));
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(err) => {
+            eprintln!("Error: {err:#}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
 }
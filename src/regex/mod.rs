@@ -0,0 +1,207 @@
+use super::transform::{get_fresh_symbol, get_grammar};
+use super::*;
+
+/// Letters with a special meaning in the pattern, written as `\x` when they are
+/// terminals.
+const OPERATORS: &str = "|*+?()\\";
+
+impl CFGrammar {
+    /// Grammar of the language of a regular expression over single letters with
+    /// `|`, `*`, `+`, `?` and parentheses, where `\` makes the next letter a
+    /// terminal. Every group and repetition becomes a fresh non-terminal, the
+    /// start one is given.
+    pub fn from_regex(pattern: &str, start: char) -> Result<CFGrammar, anyhow::Error> {
+        let mut parser = RegexParser {
+            letters: pattern.chars().collect(),
+            pos: 0,
+            used: pattern.chars().chain([start]).collect(),
+            terminals: HashSet::new(),
+            non_terminals: HashSet::from([start]),
+            rules: Vec::new(),
+        };
+
+        for rule_right in parser.parse_alternatives()? {
+            parser.rules.push((start, rule_right));
+        }
+
+        if let Some(found) = parser.letters.get(parser.pos) {
+            bail!("Unexpected '{}' at position {}.", found, parser.pos);
+        }
+
+        if parser.terminals.contains(&start) {
+            bail!("The start '{}' is a terminal of the pattern.", start);
+        }
+
+        Ok(get_grammar(
+            &parser.terminals,
+            &parser.non_terminals,
+            parser.rules,
+            start,
+        ))
+    }
+}
+
+struct RegexParser {
+    letters: Vec<char>,
+    pos: usize,
+    used: HashSet<char>,
+    terminals: HashSet<char>,
+    non_terminals: HashSet<char>,
+    rules: Vec<CFRule>,
+}
+
+impl RegexParser {
+    fn parse_alternatives(&mut self) -> Result<Vec<String>, anyhow::Error> {
+        let mut alternatives = vec![self.parse_sequence()?];
+
+        while self.letters.get(self.pos) == Some(&'|') {
+            self.pos += 1;
+            alternatives.push(self.parse_sequence()?);
+        }
+
+        Ok(alternatives)
+    }
+
+    fn parse_sequence(&mut self) -> Result<String, anyhow::Error> {
+        let mut sequence = String::new();
+
+        while let Some(letter) = self.letters.get(self.pos) {
+            if "|)".contains(*letter) {
+                break;
+            }
+
+            let item = self.parse_item()?;
+            sequence.push_str(&item);
+        }
+
+        Ok(sequence)
+    }
+
+    fn parse_item(&mut self) -> Result<String, anyhow::Error> {
+        let mut item = self.parse_atom()?;
+
+        while let Some(operator) = self
+            .letters
+            .get(self.pos)
+            .copied()
+            .filter(|letter| "*+?".contains(*letter))
+        {
+            self.pos += 1;
+            let symbol = self.add_fresh_non_terminal();
+
+            match operator {
+                '*' => {
+                    self.rules.push((symbol, format!("{}{}", item, symbol)));
+                    self.rules.push((symbol, String::new()));
+                }
+                '+' => {
+                    self.rules.push((symbol, format!("{}{}", item, symbol)));
+                    self.rules.push((symbol, item));
+                }
+                _ => {
+                    self.rules.push((symbol, item));
+                    self.rules.push((symbol, String::new()));
+                }
+            }
+
+            item = symbol.to_string();
+        }
+
+        Ok(item)
+    }
+
+    fn parse_atom(&mut self) -> Result<String, anyhow::Error> {
+        let Some(letter) = self.letters.get(self.pos).copied() else {
+            bail!("Expected a letter, found the end of the pattern.");
+        };
+        self.pos += 1;
+
+        match letter {
+            '(' => {
+                let alternatives = self.parse_alternatives()?;
+
+                if self.letters.get(self.pos) != Some(&')') {
+                    bail!("Unclosed '(' at position {}.", self.pos);
+                }
+
+                self.pos += 1;
+                let symbol = self.add_fresh_non_terminal();
+
+                for rule_right in alternatives {
+                    self.rules.push((symbol, rule_right));
+                }
+
+                Ok(symbol.to_string())
+            }
+            '\\' => match self.letters.get(self.pos).copied() {
+                Some(letter) if OPERATORS.contains(letter) => {
+                    self.pos += 1;
+                    self.terminals.insert(letter);
+                    Ok(letter.to_string())
+                }
+                _ => bail!("Only operators can be escaped, at position {}.", self.pos),
+            },
+            _ if OPERATORS.contains(letter) => {
+                bail!("Unexpected '{}' at position {}.", letter, self.pos - 1)
+            }
+            _ => {
+                self.terminals.insert(letter);
+                Ok(letter.to_string())
+            }
+        }
+    }
+
+    fn add_fresh_non_terminal(&mut self) -> char {
+        let symbol = get_fresh_symbol(&mut self.used);
+        self.non_terminals.insert(symbol);
+        symbol
+    }
+}
+
+#[cfg(all(test, feature = "earley"))]
+mod tests {
+    use super::*;
+    use crate::earley::EarleyParser;
+
+    #[test]
+    fn regex_unit_test_1() {
+        let cases = [
+            ("ab|c", &["ab", "c"][..], &["", "a", "abc", "cc"][..]),
+            (
+                "(ab|c)*d?",
+                &["", "d", "abcd", "cccab"],
+                &["dd", "a", "acd"],
+            ),
+            ("a+b*", &["a", "aab", "abbb"], &["", "b", "aba"]),
+            ("(a|)(b?)+", &["", "a", "ab", "abbb"], &["aa", "ba"]),
+            ("\\(x\\*\\)", &["(x*)"], &["x", "(x)", "(xx)"]),
+            ("", &[""], &["a"]),
+        ];
+
+        for (pattern, accepted, rejected) in cases {
+            let grammar =
+                CFGrammar::from_regex(pattern, 'S').expect("Failed to parse the pattern.");
+            assert_eq!(grammar.get_user_start(), 'S');
+            let mut parser = EarleyParser::new();
+            parser.fit(&grammar).expect("Fit unsuccessful");
+
+            for word in accepted {
+                assert!(Parser::predict(&mut parser, word), "{pattern} {word}");
+            }
+
+            for word in rejected {
+                assert!(!Parser::predict(&mut parser, word), "{pattern} {word}");
+            }
+        }
+    }
+
+    #[test]
+    fn regex_should_fail_1() {
+        assert!(CFGrammar::from_regex("(ab", 'S').is_err());
+        assert!(CFGrammar::from_regex("ab)", 'S').is_err());
+        assert!(CFGrammar::from_regex("*a", 'S').is_err());
+        assert!(CFGrammar::from_regex("a\\", 'S').is_err());
+        assert!(CFGrammar::from_regex("\\a", 'S').is_err());
+        assert!(CFGrammar::from_regex("aS", 'S').is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::{BTreeSet, HashMap};
+
+use super::*;
+
+/// Source of random numbers for sampling words.
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+}
+
+/// Small xorshift generator, good enough for sampling but not for cryptography.
+#[derive(Debug, Clone)]
+pub struct XorShiftRng {
+    state: u64,
+}
+
+impl XorShiftRng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.max(1) }
+    }
+}
+
+impl Rng for XorShiftRng {
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl CFGrammar {
+    /// Random word of the language, expanding the rules of every non-terminal
+    /// uniformly from the start. Gives up if the derivation gets deeper than
+    /// `max_depth`.
+    pub fn sample<R: Rng>(&self, rng: &mut R, max_depth: usize) -> Option<String> {
+        let mut word = String::new();
+        self.sample_symbol(rng, self.get_user_start(), max_depth, &mut word)?;
+        Some(word)
+    }
+
+    /// All words of the language of at most `max_len` letters, sorted.
+    ///
+    /// The number of words grows exponentially with the bound, so this is only
+    /// practical for small ones.
+    pub fn words_up_to(&self, max_len: usize) -> Vec<String> {
+        let mut words: HashMap<char, BTreeSet<String>> = HashMap::new();
+
+        loop {
+            let mut changed = false;
+
+            for (rule_left, rule_right) in self.rules.flat_iter() {
+                let mut found = BTreeSet::from([String::new()]);
+
+                for symbol in rule_right.chars() {
+                    let suffixes = if self.is_non_terminal(&symbol) {
+                        words.get(&symbol).cloned().unwrap_or_default()
+                    } else {
+                        BTreeSet::from([symbol.to_string()])
+                    };
+                    found = found
+                        .iter()
+                        .flat_map(|prefix| {
+                            suffixes.iter().filter_map(move |suffix| {
+                                let word = format!("{prefix}{suffix}");
+                                (word.chars().count() <= max_len).then_some(word)
+                            })
+                        })
+                        .collect();
+                }
+
+                let entry = words.entry(*rule_left).or_default();
+                let prev_size = entry.len();
+                entry.extend(found);
+                changed |= entry.len() != prev_size;
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        words
+            .remove(&self.start)
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    /// Check that both grammars derive the same words of at most `max_len`
+    /// letters. Equal languages always pass, but passing does not prove them
+    /// equal, as the languages may differ on longer words only.
+    pub fn equivalent_up_to(&self, other: &CFGrammar, max_len: usize) -> bool {
+        self.words_up_to(max_len) == other.words_up_to(max_len)
+    }
+
+    fn sample_symbol<R: Rng>(
+        &self,
+        rng: &mut R,
+        symbol: char,
+        depth: usize,
+        word: &mut String,
+    ) -> Option<()> {
+        if !self.is_non_terminal(&symbol) {
+            word.push(symbol);
+            return Some(());
+        }
+
+        let rules = self.rules.get_vec(&symbol)?;
+
+        if depth == 0 {
+            return None;
+        }
+
+        let rule_right = &rules[(rng.next_u64() % rules.len() as u64) as usize];
+
+        for next in rule_right.chars() {
+            self.sample_symbol(rng, next, depth - 1, word)?;
+        }
+
+        Some(())
+    }
+}
+
+#[cfg(all(test, feature = "earley"))]
+mod tests {
+    use super::*;
+    use crate::earley::EarleyParser;
+
+    #[test]
+    fn sample_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE")
+                .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        let mut rng = XorShiftRng::new(42);
+        let mut sampled = 0;
+
+        for _ in 0..100 {
+            if let Some(word) = grammar.sample(&mut rng, 10) {
+                assert!(Parser::predict(&mut parser, &word));
+                sampled += 1;
+            }
+        }
+
+        assert!(sampled > 0);
+        assert_eq!(
+            grammar.sample(&mut XorShiftRng::new(7), 10),
+            grammar.sample(&mut XorShiftRng::new(7), 10)
+        );
+    }
+
+    #[test]
+    fn words_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        assert_eq!(grammar.words_up_to(5), vec!["", "aabb", "ab"]);
+        assert_eq!(grammar.words_up_to(0), vec![""]);
+        let grammar = CFGrammar::from_str("SA\na\nS->AA\nA->AA\nA->S\nA->a\nA->\nS")
+            .expect("Failed to parse the grammar.");
+        assert_eq!(grammar.words_up_to(3), vec!["", "a", "aa", "aaa"]);
+    }
+
+    #[test]
+    fn words_unit_test_2() {
+        let grammar =
+            CFGrammar::from_str("ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE")
+                .expect("Failed to parse the grammar.");
+        let cnf = grammar.to_cnf();
+        assert_eq!(grammar.words_up_to(5), cnf.words_up_to(5));
+        assert!(grammar.words_up_to(5).contains(&"(a)*a".to_string()));
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for word in grammar.words_up_to(5) {
+            assert!(Parser::predict(&mut parser, &word));
+        }
+    }
+
+    #[test]
+    fn equivalent_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE")
+                .expect("Failed to parse the grammar.");
+        assert!(grammar.equivalent_up_to(&grammar.to_cnf(), 6));
+        assert!(grammar.equivalent_up_to(&grammar.eliminate_left_recursion(), 6));
+        let grammar = CFGrammar::from_str("SA\nab\nS->aSb\nS->A\nA->b\nA->\nS")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.equivalent_up_to(&grammar.remove_epsilon(), 6));
+        let other = CFGrammar::from_str("SA\nab\nS->aSb\nS->A\nA->bb\nA->\nS")
+            .expect("Failed to parse the grammar.");
+        assert!(grammar.equivalent_up_to(&other, 0));
+        assert!(!grammar.equivalent_up_to(&other, 3));
+    }
+
+    #[test]
+    fn sample_unit_test_2() {
+        let grammar = CFGrammar::from_str("S\na\nS->aS\nS").expect("Failed to parse the grammar.");
+        assert!(grammar.sample(&mut XorShiftRng::new(1), 100).is_none());
+        let grammar =
+            CFGrammar::from_str("S\na\nS->a\nS->\nS").expect("Failed to parse the grammar.");
+        let word = grammar
+            .sample(&mut XorShiftRng::new(1), 1)
+            .expect("Sample unsuccessful");
+        assert!(word.is_empty() || word == "a");
+    }
+}
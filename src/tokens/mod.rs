@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use super::transform::get_grammar;
+use super::*;
+
+/// First symbol interned for a name, the start of the supplementary private use
+/// area, so interned symbols never clash with the ones written by hand.
+const FIRST_INTERNED: u32 = 0xF0000;
+
+/// Piece of a rule in the token or the BNF notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TokenItem {
+    /// Name of a non-terminal, bare or in angle brackets.
+    Name(String),
+    /// Quoted terminal.
+    Terminal(String),
+    Define,
+    Alternative,
+}
+
+/// Notation of the rules read by `get_token_items`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Notation {
+    /// `expr -> expr "+" term`, names are bare.
+    Tokens,
+    /// `<expr> ::= <expr> "+" <term>`, names are in angle brackets.
+    Bnf,
+}
+
+/// Names of the symbols of a grammar read with `CFGrammar::from_str_tokens` or
+/// `CFGrammar::from_bnf`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Vocabulary {
+    /// Symbols of the terminals by their names.
+    terminals: HashMap<String, char>,
+    /// Names of all interned symbols.
+    names: HashMap<char, String>,
+}
+
+/// Parse tree with the interned symbols replaced by their names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamedTree {
+    /// Terminal token.
+    Leaf(String),
+    /// Non-terminal with the subtrees of its right part.
+    Node {
+        name: String,
+        children: Vec<NamedTree>,
+    },
+}
+
+impl CFGrammar {
+    /// Parse rules like `expr -> expr "+" term | term`, one per line, where the
+    /// symbols are separated with whitespace. Quoted names are terminals, bare
+    /// names are non-terminals, and the left part of the first rule is the start.
+    /// Every name is interned as a unique private symbol, the returned vocabulary
+    /// translates between them.
+    pub fn from_str_tokens(s: &str) -> Result<(CFGrammar, Vocabulary), anyhow::Error> {
+        let mut vocabulary = Vocabulary::default();
+        let mut non_terminals = HashMap::<String, char>::new();
+        let mut defined = HashSet::new();
+        let mut rules = Vec::new();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let items = get_token_items(line, Notation::Tokens)?;
+            let [TokenItem::Name(name), TokenItem::Define, ..] = &items[..] else {
+                bail!("Every rule must start with 'name ->'.");
+            };
+
+            let rule_left = vocabulary.intern_non_terminal(&mut non_terminals, name)?;
+            defined.insert(rule_left);
+            let mut rule_right = String::new();
+
+            for item in items[2..].iter() {
+                match item {
+                    TokenItem::Name(name) => {
+                        rule_right.push(vocabulary.intern_non_terminal(&mut non_terminals, name)?);
+                    }
+                    TokenItem::Terminal(name) if name.is_empty() => {
+                        bail!("Terminal names must not be empty.");
+                    }
+                    TokenItem::Terminal(name) => {
+                        rule_right.push(vocabulary.intern_terminal(name)?);
+                    }
+                    TokenItem::Alternative => {
+                        rules.push((rule_left, std::mem::take(&mut rule_right)));
+                    }
+                    TokenItem::Define => bail!("Unexpected '->'."),
+                }
+            }
+
+            rules.push((rule_left, rule_right));
+        }
+
+        if let Some((name, _)) = non_terminals
+            .iter()
+            .find(|(_, symbol)| !defined.contains(*symbol))
+        {
+            bail!("Non-terminal {name:?} has no rules.");
+        }
+
+        let Some(start) = rules.first().map(|rule| rule.0) else {
+            bail!("There must be at least one rule.");
+        };
+
+        let terminals = vocabulary.terminals.values().copied().collect();
+        let non_terminals = non_terminals.into_values().collect();
+        let grammar = get_grammar(&terminals, &non_terminals, rules, start);
+        Ok((grammar, vocabulary))
+    }
+}
+
+impl Vocabulary {
+    /// Vocabulary of the named symbols, the terminals are named by themselves.
+    pub(crate) fn from_names(names: HashMap<char, String>, terminals: &HashSet<char>) -> Self {
+        Self {
+            terminals: terminals
+                .iter()
+                .map(|symbol| (symbol.to_string(), *symbol))
+                .collect(),
+            names,
+        }
+    }
+
+    /// Symbol of the terminal token.
+    pub fn letter(&self, token: &str) -> Option<char> {
+        self.terminals.get(token).copied()
+    }
+
+    /// Name of the interned symbol.
+    pub fn name(&self, symbol: char) -> Option<&str> {
+        self.names.get(&symbol).map(String::as_str)
+    }
+
+    /// Translate the tokens of a word into the letters the parsers work with.
+    pub fn letters(&self, tokens: &[&str]) -> Result<Vec<char>, anyhow::Error> {
+        tokens
+            .iter()
+            .map(|token| match self.letter(token) {
+                Some(letter) => Ok(letter),
+                None => bail!("Unknown token {token:?}."),
+            })
+            .collect()
+    }
+
+    /// Check if the word made of the tokens is in the language of the parser.
+    pub fn predict_tokens<P: Parser>(&self, parser: &mut P, tokens: &[&str]) -> bool {
+        self.letters(tokens)
+            .is_ok_and(|letters| parser.predict(&String::from_iter(letters)))
+    }
+
+    /// Replace the symbols of the tree with their names. Symbols without a name
+    /// are kept as they are.
+    pub fn relabel(&self, tree: &ParseTree) -> NamedTree {
+        let get_name = |symbol| match self.name(symbol) {
+            Some(name) => name.to_string(),
+            None => symbol.to_string(),
+        };
+
+        match tree {
+            ParseTree::Leaf(letter) => NamedTree::Leaf(get_name(*letter)),
+            ParseTree::Node { rule, children } => NamedTree::Node {
+                name: get_name(rule.0),
+                children: children.iter().map(|child| self.relabel(child)).collect(),
+            },
+        }
+    }
+
+    fn intern_terminal(&mut self, name: &str) -> Result<char, anyhow::Error> {
+        if let Some(symbol) = self.terminals.get(name) {
+            return Ok(*symbol);
+        }
+
+        let symbol = self.get_next_symbol()?;
+        self.terminals.insert(name.to_string(), symbol);
+        self.names.insert(symbol, name.to_string());
+        Ok(symbol)
+    }
+
+    fn intern_non_terminal(
+        &mut self,
+        non_terminals: &mut HashMap<String, char>,
+        name: &str,
+    ) -> Result<char, anyhow::Error> {
+        if let Some(symbol) = non_terminals.get(name) {
+            return Ok(*symbol);
+        }
+
+        let symbol = self.get_next_symbol()?;
+        non_terminals.insert(name.to_string(), symbol);
+        self.names.insert(symbol, name.to_string());
+        Ok(symbol)
+    }
+
+    fn get_next_symbol(&self) -> Result<char, anyhow::Error> {
+        match char::from_u32(FIRST_INTERNED + self.names.len() as u32) {
+            Some(symbol) => Ok(symbol),
+            None => bail!(
+                "There are no unused symbols left for {} names.",
+                self.names.len() + 1
+            ),
+        }
+    }
+}
+
+/// Split the rules into names, quoted terminals, definitions and alternatives.
+pub(crate) fn get_token_items(
+    s: &str,
+    notation: Notation,
+) -> Result<Vec<TokenItem>, anyhow::Error> {
+    let mut items = Vec::new();
+    let mut letters = s.chars().peekable();
+
+    while let Some(letter) = letters.next() {
+        match (letter, notation) {
+            ('"' | '\'', _) => {
+                let mut terminal = String::new();
+
+                loop {
+                    match letters.next() {
+                        Some(next) if next == letter => break,
+                        Some(next) => terminal.push(next),
+                        None => bail!("Unterminated terminal string."),
+                    }
+                }
+
+                items.push(TokenItem::Terminal(terminal));
+            }
+            ('|', _) => items.push(TokenItem::Alternative),
+            (_, _) if letter.is_whitespace() => {}
+            ('-', Notation::Tokens) if letters.peek() == Some(&'>') => {
+                letters.next();
+                items.push(TokenItem::Define);
+            }
+            (_, Notation::Tokens) => {
+                let mut name = letter.to_string();
+
+                while let Some(next) = letters.peek() {
+                    if next.is_whitespace() || ['"', '\'', '|'].contains(next) {
+                        break;
+                    }
+
+                    name.push(*next);
+                    letters.next();
+                }
+
+                items.push(TokenItem::Name(name));
+            }
+            ('<', Notation::Bnf) => {
+                let mut name = String::new();
+
+                loop {
+                    match letters.next() {
+                        Some('>') => break,
+                        Some(next) => name.push(next),
+                        None => bail!("Unterminated non-terminal <{name}."),
+                    }
+                }
+
+                if name.is_empty() {
+                    bail!("Non-terminal names must not be empty.");
+                }
+
+                items.push(TokenItem::Name(name));
+            }
+            (':', Notation::Bnf) => {
+                if letters.next() != Some(':') || letters.next() != Some('=') {
+                    bail!("Expected '::='.");
+                }
+
+                items.push(TokenItem::Define);
+            }
+            (_, Notation::Bnf) => bail!("Unexpected symbol '{}'.", letter),
+        }
+    }
+
+    Ok(items)
+}
+
+#[cfg(all(test, feature = "earley"))]
+mod tests {
+    use super::*;
+    use crate::earley::EarleyParser;
+
+    #[test]
+    fn tokens_unit_test_1() {
+        let (grammar, vocabulary) = CFGrammar::from_str_tokens(
+            "# Statements with keywords.
+             stmt -> \"if\" cond \"then\" stmt | \"if\" cond \"then\" stmt \"else\" stmt
+             stmt -> \"print\" 'x'
+             cond -> 'x' \"==\" 'x' |",
+        )
+        .expect("Failed to parse the grammar.");
+        let mut parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+
+        for tokens in [
+            &["print", "x"][..],
+            &["if", "x", "==", "x", "then", "print", "x"],
+            &["if", "then", "print", "x", "else", "print", "x"],
+        ] {
+            assert!(vocabulary.predict_tokens(&mut parser, tokens), "{tokens:?}");
+        }
+
+        for tokens in [&["print"][..], &["if", "x", "then"], &["print", "y"], &[]] {
+            assert!(
+                !vocabulary.predict_tokens(&mut parser, tokens),
+                "{tokens:?}"
+            );
+        }
+
+        assert_eq!(grammar.terminals().count(), 6);
+        assert!(vocabulary.letters(&["print", "y"]).is_err());
+        let letters = vocabulary.letters(&["print", "x"]).unwrap();
+        let tree = parser
+            .parse(&String::from_iter(letters))
+            .expect("Parse unsuccessful");
+        let leaf = |name: &str| NamedTree::Leaf(name.to_string());
+        assert_eq!(
+            vocabulary.relabel(&tree),
+            NamedTree::Node {
+                name: "stmt".to_string(),
+                children: vec![leaf("print"), leaf("x")],
+            }
+        );
+    }
+
+    #[test]
+    fn tokens_should_fail_1() {
+        assert!(CFGrammar::from_str_tokens("").is_err());
+        assert!(CFGrammar::from_str_tokens("s -> a").is_err());
+        assert!(CFGrammar::from_str_tokens("s = 'a'").is_err());
+        assert!(CFGrammar::from_str_tokens("s -> 'a").is_err());
+        assert!(CFGrammar::from_str_tokens("s -> ''").is_err());
+        assert!(CFGrammar::from_str_tokens("s -> 'a' -> 'b'").is_err());
+    }
+
+    #[test]
+    fn tokens_should_fail_2() {
+        // One name more than there are symbols in the private use planes.
+        let terminals: Vec<_> = (0..0x20000).map(|i| format!("'t{i}'")).collect();
+        let result = CFGrammar::from_str_tokens(&format!("s -> {}", terminals.join(" ")));
+        assert_eq!(
+            result.map(|_| ()).unwrap_err().to_string(),
+            "There are no unused symbols left for 131073 names."
+        );
+    }
+}
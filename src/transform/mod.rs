@@ -0,0 +1,989 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use super::*;
+
+impl CFGrammar {
+    /// Equivalent grammar in the Chomsky normal form.
+    ///
+    /// Every rule has either the form `A -> BC` or `A -> a`, where the fresh
+    /// start non-terminal never appears in a right part. Since such rules can not
+    /// derive the empty word, the start non-terminal additionally gets an empty
+    /// rule if and only if the empty word is in the language.
+    pub fn to_cnf(&self) -> CFGrammar {
+        let mut used = self.get_used_symbols();
+        let terminals = self.get_user_terminals();
+        let mut non_terminals = self.get_user_non_terminals();
+        let mut rules = self.get_user_rules();
+        let start = get_fresh_symbol(&mut used);
+        non_terminals.insert(start);
+        rules.insert((start, self.get_user_start().to_string()));
+
+        // Replace terminals in long right parts with wrapping non-terminals.
+        let mut wrappers = HashMap::new();
+        let mut new_rules = BTreeSet::new();
+
+        for (rule_left, rule_right) in rules {
+            if rule_right.chars().count() < 2 {
+                new_rules.insert((rule_left, rule_right));
+                continue;
+            }
+
+            let mut new_right = String::new();
+
+            for symbol in rule_right.chars() {
+                if non_terminals.contains(&symbol) {
+                    new_right.push(symbol);
+                } else {
+                    let wrapper = *wrappers
+                        .entry(symbol)
+                        .or_insert_with(|| get_fresh_symbol(&mut used));
+                    new_right.push(wrapper);
+                }
+            }
+
+            new_rules.insert((rule_left, new_right));
+        }
+
+        for (symbol, wrapper) in wrappers {
+            non_terminals.insert(wrapper);
+            new_rules.insert((wrapper, symbol.to_string()));
+        }
+
+        // Split long right parts into chains of binary rules.
+        rules = BTreeSet::new();
+
+        for (rule_left, rule_right) in new_rules {
+            let symbols: Vec<_> = rule_right.chars().collect();
+            let tail = symbols.len().saturating_sub(2);
+            let mut curr_left = rule_left;
+
+            for symbol in symbols[..tail].iter() {
+                let next_left = get_fresh_symbol(&mut used);
+                non_terminals.insert(next_left);
+                rules.insert((curr_left, String::from_iter([*symbol, next_left])));
+                curr_left = next_left;
+            }
+
+            rules.insert((curr_left, String::from_iter(&symbols[tail..])));
+        }
+
+        // Drop the empty rules, keeping the empty word for the start only.
+        let nullable = get_nullable(&rules);
+        let new_rules = get_non_empty_rules(&rules, &nullable);
+
+        // Replace chains of unit rules with their non-unit targets.
+        let index = get_rules_index(&new_rules);
+        rules = BTreeSet::new();
+
+        for rule_left in non_terminals.iter().copied() {
+            let mut reached = HashSet::from([rule_left]);
+            let mut queue = vec![rule_left];
+
+            while let Some(curr_left) = queue.pop() {
+                for rule_right in index.get(&curr_left).into_iter().flatten() {
+                    let mut symbols = rule_right.chars();
+
+                    match (symbols.next(), symbols.next()) {
+                        (Some(symbol), None) if non_terminals.contains(&symbol) => {
+                            if reached.insert(symbol) {
+                                queue.push(symbol);
+                            }
+                        }
+                        _ => {
+                            rules.insert((rule_left, rule_right.to_string()));
+                        }
+                    }
+                }
+            }
+        }
+
+        if nullable.contains(&start) {
+            rules.insert((start, String::new()));
+        }
+
+        let rules = get_useful_rules(&rules, &non_terminals, start);
+        let non_terminals = rules.iter().map(|rule| rule.0).chain([start]).collect();
+        get_grammar(&terminals, &non_terminals, rules, start)
+    }
+
+    /// Equivalent grammar in the Greibach normal form.
+    ///
+    /// Every rule has the form `A -> aB...` with a terminal followed by zero or more
+    /// non-terminals. Fails if the empty word is in the language.
+    pub fn to_gnf(&self) -> Result<CFGrammar, anyhow::Error> {
+        if self.accepts_empty() {
+            bail!("The empty word can not be derived in the Greibach normal form.");
+        }
+
+        let cnf = self.to_cnf();
+        let start = cnf.get_user_start();
+        let terminals = cnf.get_user_terminals();
+        let mut non_terminals = cnf.get_user_non_terminals();
+        let mut used = cnf.get_used_symbols();
+        let mut rules = BTreeMap::<char, BTreeSet<String>>::new();
+
+        for (rule_left, rule_right) in cnf.get_user_rules() {
+            if rule_right.is_empty() {
+                bail!("The empty word can not be derived in the Greibach normal form.");
+            }
+
+            rules.entry(rule_left).or_default().insert(rule_right);
+        }
+
+        let order: Vec<_> = rules.keys().copied().collect();
+        let mut tails = BTreeMap::new();
+
+        // Make every rule of the i-th non-terminal start with a terminal or a later one.
+        for (i, rule_left) in order.iter().enumerate() {
+            for prev_left in order[..i].iter() {
+                let substituted = substitute(&rules, &rules[rule_left], *prev_left);
+                rules.insert(*rule_left, substituted);
+            }
+
+            let (recursive, other): (BTreeSet<_>, BTreeSet<_>) = rules[rule_left]
+                .iter()
+                .cloned()
+                .partition(|rule_right| rule_right.starts_with(*rule_left));
+
+            if recursive.is_empty() {
+                continue;
+            }
+
+            let tail = get_fresh_symbol(&mut used);
+            non_terminals.insert(tail);
+            let mut new_rules = other.clone();
+            new_rules.extend(other.iter().map(|rule_right| format!("{rule_right}{tail}")));
+            rules.insert(*rule_left, new_rules);
+            let mut tail_rules = BTreeSet::new();
+
+            for rule_right in recursive {
+                let rest: String = rule_right.chars().skip(1).collect();
+                tail_rules.insert(format!("{rest}{tail}"));
+                tail_rules.insert(rest);
+            }
+
+            tails.insert(tail, tail_rules);
+        }
+
+        // The last non-terminal already starts with terminals only, go backwards.
+        for (i, rule_left) in order.iter().enumerate().rev() {
+            for next_left in order[(i + 1)..].iter() {
+                let substituted = substitute(&rules, &rules[rule_left], *next_left);
+                rules.insert(*rule_left, substituted);
+            }
+        }
+
+        for (tail, mut tail_rules) in tails {
+            for rule_left in order.iter() {
+                tail_rules = substitute(&rules, &tail_rules, *rule_left);
+            }
+
+            rules.insert(tail, tail_rules);
+        }
+
+        let rules: BTreeSet<_> = rules
+            .into_iter()
+            .flat_map(|(rule_left, rules_right)| {
+                rules_right
+                    .into_iter()
+                    .map(move |rule_right| (rule_left, rule_right))
+            })
+            .collect();
+        let rules = get_useful_rules(&rules, &non_terminals, start);
+        let non_terminals = rules.iter().map(|rule| rule.0).chain([start]).collect();
+        Ok(get_grammar(&terminals, &non_terminals, rules, start))
+    }
+
+    /// Equivalent grammar without empty rules.
+    ///
+    /// If the empty word is in the language, the start non-terminal keeps a single
+    /// empty rule. A fresh start is introduced for it when the old one appears in
+    /// a right part.
+    pub fn remove_epsilon(&self) -> CFGrammar {
+        let terminals = self.get_user_terminals();
+        let mut non_terminals = self.get_user_non_terminals();
+        let rules = self.get_user_rules();
+        let mut start = self.get_user_start();
+        let nullable = self.nullable();
+        let mut new_rules = get_non_empty_rules(&rules, &nullable);
+
+        if nullable.contains(&start) {
+            if rules
+                .iter()
+                .any(|(_, rule_right)| rule_right.contains(start))
+            {
+                let new_start = get_fresh_symbol(&mut self.get_used_symbols());
+                non_terminals.insert(new_start);
+                new_rules.insert((new_start, start.to_string()));
+                start = new_start;
+            }
+
+            new_rules.insert((start, String::new()));
+        }
+
+        let new_rules = get_useful_rules(&new_rules, &non_terminals, start);
+        get_grammar(&terminals, &non_terminals, new_rules, start)
+    }
+
+    /// Equivalent grammar where no two rules of a non-terminal share a prefix.
+    ///
+    /// Rules `A -> αβ` and `A -> αγ` become `A -> αA'`, `A' -> β` and `A' -> γ`
+    /// for a fresh `A'` until nothing can be factored anymore.
+    pub fn left_factor(&self) -> CFGrammar {
+        let mut used = self.get_used_symbols();
+        let terminals = self.get_user_terminals();
+        let mut non_terminals = self.get_user_non_terminals();
+        let mut rules = self.get_user_rules();
+
+        loop {
+            let index = get_rules_index(&rules);
+            let mut factored = None;
+
+            'search: for (rule_left, rules_right) in index.iter() {
+                for rule_right in rules_right.iter() {
+                    let Some(first) = rule_right.chars().next() else {
+                        continue;
+                    };
+                    let group: Vec<_> = rules_right
+                        .iter()
+                        .filter(|other| other.starts_with(first))
+                        .collect();
+
+                    if group.len() < 2 {
+                        continue;
+                    }
+
+                    let mut prefix: Vec<_> = rule_right.chars().collect();
+
+                    for other in group.iter() {
+                        let common = prefix
+                            .iter()
+                            .zip(other.chars())
+                            .take_while(|(symbol, other)| **symbol == *other)
+                            .count();
+                        prefix.truncate(common);
+                    }
+
+                    let prefix = String::from_iter(prefix);
+                    let group = group
+                        .into_iter()
+                        .map(|rule_right| rule_right.to_string())
+                        .collect::<Vec<_>>();
+                    factored = Some((*rule_left, prefix, group));
+                    break 'search;
+                }
+            }
+
+            let Some((rule_left, prefix, group)) = factored else {
+                break;
+            };
+
+            let new_left = get_fresh_symbol(&mut used);
+            non_terminals.insert(new_left);
+            rules.insert((rule_left, format!("{}{}", prefix, new_left)));
+
+            for rule_right in group {
+                rules.remove(&(rule_left, rule_right.clone()));
+                rules.insert((new_left, rule_right[prefix.len()..].to_string()));
+            }
+        }
+
+        get_grammar(&terminals, &non_terminals, rules, self.get_user_start())
+    }
+
+    /// Equivalent grammar without left recursion (Paull's algorithm).
+    ///
+    /// Empty rules are removed first, then `A -> Aα | β` becomes `A -> βA'` and
+    /// `A' -> αA' | ε` for a fresh `A'`.
+    pub fn eliminate_left_recursion(&self) -> CFGrammar {
+        let grammar = self.remove_epsilon();
+        let start = grammar.get_user_start();
+        let terminals = grammar.get_user_terminals();
+        let mut non_terminals = grammar.get_user_non_terminals();
+        let mut used = grammar.get_used_symbols();
+        let mut rules = BTreeMap::<char, BTreeSet<String>>::new();
+
+        for (rule_left, rule_right) in grammar.get_user_rules() {
+            rules.entry(rule_left).or_default().insert(rule_right);
+        }
+
+        let order: Vec<_> = rules.keys().copied().collect();
+
+        for (i, rule_left) in order.iter().enumerate() {
+            for prev_left in order[..i].iter() {
+                let substituted = substitute(&rules, &rules[rule_left], *prev_left);
+                rules.insert(*rule_left, substituted);
+            }
+
+            let (recursive, other): (BTreeSet<_>, BTreeSet<_>) = rules[rule_left]
+                .iter()
+                .cloned()
+                .partition(|rule_right| rule_right.starts_with(*rule_left));
+
+            if recursive.is_empty() {
+                continue;
+            }
+
+            let tail = get_fresh_symbol(&mut used);
+            non_terminals.insert(tail);
+            let new_rules = other
+                .iter()
+                .map(|rule_right| format!("{rule_right}{tail}"))
+                .collect();
+            rules.insert(*rule_left, new_rules);
+            let mut tail_rules = BTreeSet::from([String::new()]);
+
+            // Rules `A -> A` only come from unit cycles and derive nothing new.
+            for rule_right in recursive {
+                let rest: String = rule_right.chars().skip(1).collect();
+
+                if !rest.is_empty() {
+                    tail_rules.insert(format!("{rest}{tail}"));
+                }
+            }
+
+            rules.insert(tail, tail_rules);
+        }
+
+        let rules: BTreeSet<_> = rules
+            .into_iter()
+            .flat_map(|(rule_left, rules_right)| {
+                rules_right
+                    .into_iter()
+                    .map(move |rule_right| (rule_left, rule_right))
+            })
+            .collect();
+        let rules = get_useful_rules(&rules, &non_terminals, start);
+        let non_terminals = rules.iter().map(|rule| rule.0).chain([start]).collect();
+        get_grammar(&terminals, &non_terminals, rules, start)
+    }
+
+    /// Grammar of the union of both languages.
+    ///
+    /// Clashing non-terminals are renamed, terminals are shared.
+    pub fn union(&self, other: &CFGrammar) -> CFGrammar {
+        self.combine(other, |start, other_start| {
+            vec![start.to_string(), other_start.to_string()]
+        })
+    }
+
+    /// Grammar of the concatenation of both languages.
+    ///
+    /// Clashing non-terminals are renamed, terminals are shared.
+    pub fn concat(&self, other: &CFGrammar) -> CFGrammar {
+        self.combine(other, |start, other_start| {
+            vec![format!("{}{}", start, other_start)]
+        })
+    }
+
+    /// Grammar of the reversed language, every right part is read backwards.
+    pub fn reverse(&self) -> CFGrammar {
+        let rules = self
+            .get_user_rules()
+            .into_iter()
+            .map(|(rule_left, rule_right)| (rule_left, rule_right.chars().rev().collect()));
+        get_grammar(
+            &self.get_user_terminals(),
+            &self.get_user_non_terminals(),
+            rules,
+            self.get_user_start(),
+        )
+    }
+
+    fn combine<F>(&self, other: &CFGrammar, get_start_rules: F) -> CFGrammar
+    where
+        F: Fn(char, char) -> Vec<String>,
+    {
+        let mut used: HashSet<_> = self
+            .get_used_symbols()
+            .union(&other.get_used_symbols())
+            .copied()
+            .collect();
+        let (mut rules, mut non_terminals, start) =
+            self.get_renamed(&other.get_user_terminals(), &mut used);
+        let self_used = rules
+            .iter()
+            .flat_map(|(rule_left, rule_right)| rule_right.chars().chain([*rule_left]))
+            .chain(non_terminals.iter().copied())
+            .chain(self.get_user_terminals())
+            .collect();
+        let (other_rules, other_non_terminals, other_start) =
+            other.get_renamed(&self_used, &mut used);
+        let terminals = self
+            .get_user_terminals()
+            .union(&other.get_user_terminals())
+            .copied()
+            .collect();
+        let new_start = get_fresh_symbol(&mut used);
+        rules.extend(other_rules);
+        non_terminals.extend(other_non_terminals);
+        non_terminals.insert(new_start);
+
+        for rule_right in get_start_rules(start, other_start) {
+            rules.insert((new_start, rule_right));
+        }
+
+        get_grammar(&terminals, &non_terminals, rules, new_start)
+    }
+
+    /// User rules, non-terminals and start with the avoided non-terminals renamed.
+    fn get_renamed(
+        &self,
+        avoid: &HashSet<char>,
+        used: &mut HashSet<char>,
+    ) -> (BTreeSet<CFRule>, HashSet<char>, char) {
+        let mapping: HashMap<_, _> = self
+            .get_user_non_terminals()
+            .into_iter()
+            .filter(|symbol| avoid.contains(symbol))
+            .map(|symbol| (symbol, get_fresh_symbol(used)))
+            .collect();
+        let rename = |symbol: char| *mapping.get(&symbol).unwrap_or(&symbol);
+        let rules = self
+            .get_user_rules()
+            .into_iter()
+            .map(|(rule_left, rule_right)| {
+                (rename(rule_left), rule_right.chars().map(rename).collect())
+            })
+            .collect();
+        let non_terminals = self
+            .get_user_non_terminals()
+            .into_iter()
+            .map(rename)
+            .collect();
+        (rules, non_terminals, rename(self.get_user_start()))
+    }
+
+    pub(crate) fn get_user_start(&self) -> char {
+        self.rules
+            .get(&START_RULE)
+            .and_then(|rule_right| rule_right.chars().next())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get_user_terminals(&self) -> HashSet<char> {
+        self.terminals
+            .iter()
+            .copied()
+            .filter(|symbol| *symbol != END_TERMINAL && *symbol != EPS_TERMINAL)
+            .collect()
+    }
+
+    pub(crate) fn get_user_non_terminals(&self) -> HashSet<char> {
+        self.non_terminals
+            .iter()
+            .copied()
+            .filter(|symbol| *symbol != START_RULE)
+            .collect()
+    }
+
+    pub(crate) fn get_user_rules(&self) -> BTreeSet<CFRule> {
+        self.rules
+            .flat_iter()
+            .filter(|(rule_left, _)| **rule_left != START_RULE)
+            .map(|(rule_left, rule_right)| (*rule_left, rule_right.clone()))
+            .collect()
+    }
+
+    pub(crate) fn get_used_symbols(&self) -> HashSet<char> {
+        let mut used: HashSet<_> = self.terminals.union(&self.non_terminals).copied().collect();
+
+        for (_, rule_right) in self.rules.flat_iter() {
+            used.extend(rule_right.chars());
+        }
+
+        used
+    }
+}
+
+/// Allocate a symbol that is not used yet.
+pub(crate) fn get_fresh_symbol(used: &mut HashSet<char>) -> char {
+    let symbol = ('A'..='Z')
+        .chain('\u{E000}'..='\u{F8FF}')
+        .find(|symbol| !used.contains(symbol))
+        .expect("There are no unused symbols left.");
+    used.insert(symbol);
+    symbol
+}
+
+/// Allocate `count` symbols that are not used yet, failing if there are not
+/// enough of them left.
+pub(crate) fn get_fresh_symbols(
+    used: &mut HashSet<char>,
+    count: usize,
+) -> Result<Vec<char>, anyhow::Error> {
+    let symbols: Vec<_> = ('A'..='Z')
+        .chain('\u{E000}'..='\u{F8FF}')
+        .filter(|symbol| !used.contains(symbol))
+        .take(count)
+        .collect();
+
+    if symbols.len() < count {
+        bail!("There are no unused symbols left for {count} new symbols.");
+    }
+
+    used.extend(symbols.iter().copied());
+    Ok(symbols)
+}
+
+pub(crate) fn get_grammar(
+    terminals: &HashSet<char>,
+    non_terminals: &HashSet<char>,
+    rules: impl IntoIterator<Item = CFRule>,
+    start: char,
+) -> CFGrammar {
+    let mut rules_map = MultiMap::new();
+
+    for (rule_left, rule_right) in rules {
+        rules_map.insert(rule_left, rule_right);
+    }
+
+    CFGrammar::new(terminals, non_terminals, &rules_map, start)
+}
+
+/// Expand the leading occurrences of the non-terminal with its rules.
+fn substitute(
+    rules: &BTreeMap<char, BTreeSet<String>>,
+    rules_right: &BTreeSet<String>,
+    symbol: char,
+) -> BTreeSet<String> {
+    let mut substituted = BTreeSet::new();
+
+    for rule_right in rules_right.iter() {
+        if !rule_right.starts_with(symbol) {
+            substituted.insert(rule_right.clone());
+            continue;
+        }
+
+        let rest: String = rule_right.chars().skip(1).collect();
+
+        for prefix in rules.get(&symbol).into_iter().flatten() {
+            substituted.insert(format!("{prefix}{rest}"));
+        }
+    }
+
+    substituted
+}
+
+fn get_rules_index(rules: &BTreeSet<CFRule>) -> HashMap<char, Vec<&str>> {
+    let mut index = HashMap::<_, Vec<_>>::new();
+
+    for (rule_left, rule_right) in rules.iter() {
+        index
+            .entry(*rule_left)
+            .or_default()
+            .push(rule_right.as_str());
+    }
+
+    index
+}
+
+/// Non-terminals deriving the empty word.
+pub(crate) fn get_nullable(rules: &BTreeSet<CFRule>) -> HashSet<char> {
+    let mut nullable = HashSet::new();
+
+    loop {
+        let mut changed = false;
+
+        for (rule_left, rule_right) in rules.iter() {
+            if !nullable.contains(rule_left)
+                && rule_right.chars().all(|symbol| nullable.contains(&symbol))
+            {
+                nullable.insert(*rule_left);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    nullable
+}
+
+/// Non-empty versions of the rules with nullable symbols optionally omitted.
+fn get_non_empty_rules(rules: &BTreeSet<CFRule>, nullable: &HashSet<char>) -> BTreeSet<CFRule> {
+    let mut new_rules = BTreeSet::new();
+
+    for (rule_left, rule_right) in rules.iter() {
+        for variant in get_variants(rule_right, nullable) {
+            if !variant.is_empty() {
+                new_rules.insert((*rule_left, variant));
+            }
+        }
+    }
+
+    new_rules
+}
+
+/// All versions of the word with nullable symbols optionally omitted.
+fn get_variants(word: &str, nullable: &HashSet<char>) -> BTreeSet<String> {
+    let mut variants = BTreeSet::from([String::new()]);
+
+    for symbol in word.chars() {
+        let mut new_variants = BTreeSet::new();
+
+        for variant in variants {
+            if nullable.contains(&symbol) {
+                new_variants.insert(variant.clone());
+            }
+
+            new_variants.insert(variant + &symbol.to_string());
+        }
+
+        variants = new_variants;
+    }
+
+    variants
+}
+
+/// Rules that are reachable from the start and use productive symbols only.
+pub(crate) fn get_useful_rules(
+    rules: &BTreeSet<CFRule>,
+    non_terminals: &HashSet<char>,
+    start: char,
+) -> BTreeSet<CFRule> {
+    let mut productive = HashSet::new();
+
+    loop {
+        let mut changed = false;
+
+        for (rule_left, rule_right) in rules.iter() {
+            if !productive.contains(rule_left)
+                && rule_right
+                    .chars()
+                    .all(|symbol| !non_terminals.contains(&symbol) || productive.contains(&symbol))
+            {
+                productive.insert(*rule_left);
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let productive_rules: BTreeSet<_> = rules
+        .iter()
+        .filter(|(rule_left, rule_right)| {
+            productive.contains(rule_left)
+                && rule_right
+                    .chars()
+                    .all(|symbol| !non_terminals.contains(&symbol) || productive.contains(&symbol))
+        })
+        .cloned()
+        .collect();
+    let index = get_rules_index(&productive_rules);
+    let mut reached = HashSet::from([start]);
+    let mut queue = vec![start];
+
+    while let Some(rule_left) = queue.pop() {
+        for rule_right in index.get(&rule_left).into_iter().flatten() {
+            for symbol in rule_right.chars() {
+                if non_terminals.contains(&symbol) && reached.insert(symbol) {
+                    queue.push(symbol);
+                }
+            }
+        }
+    }
+
+    productive_rules
+        .into_iter()
+        .filter(|(rule_left, _)| reached.contains(rule_left))
+        .collect()
+}
+
+#[cfg(all(test, feature = "earley"))]
+mod tests {
+    use super::*;
+    use crate::earley::EarleyParser;
+
+    #[test]
+    fn cnf_unit_test_1() {
+        let grammar = get_test_grammar();
+        let cnf = grammar.to_cnf();
+        assert!(is_cnf(&cnf));
+        assert_same_language(
+            &grammar,
+            &cnf,
+            &["a", "(a+a)*a", "a*(a)", "a+", "()", "", "a**a"],
+        );
+    }
+
+    #[test]
+    fn cnf_unit_test_2() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        let cnf = grammar.to_cnf();
+        assert!(is_cnf(&cnf));
+        assert_same_language(&grammar, &cnf, &["", "ab", "aabb", "aab", "ba", "abab"]);
+    }
+
+    #[test]
+    fn cnf_unit_test_3() {
+        let grammar =
+            CFGrammar::from_str("SABC\nabc\nS->ABC\nA->aA\nA->\nB->b\nB->C\nC->c\nC->\nS")
+                .expect("Failed to parse the grammar.");
+        let cnf = grammar.to_cnf();
+        assert!(is_cnf(&cnf));
+        assert_same_language(
+            &grammar,
+            &cnf,
+            &["", "a", "aab", "ac", "bc", "aaacc", "ba", "ca"],
+        );
+    }
+
+    #[test]
+    fn cnf_unit_test_4() {
+        // The language is empty, only the start symbol is left.
+        let grammar =
+            CFGrammar::from_str("SA\nab\nS->baS\nA->a\nS").expect("Failed to parse the grammar.");
+        let cnf = grammar.to_cnf();
+        assert!(is_cnf(&cnf));
+        assert!(cnf.get_user_rules().is_empty());
+        // The format needs at least one rule, so an unproductive one is added to
+        // read the declarations back.
+        let start = cnf.get_user_start();
+        let text = cnf.to_string();
+        assert_eq!(text, format!("{start}\nab\n{start}"));
+        let (declarations, _) = text.rsplit_once('\n').unwrap();
+        let read = CFGrammar::from_str(&format!("{declarations}\n{start}->{start}\n{start}"))
+            .expect("Failed to parse the grammar.");
+        assert_eq!(read.get_user_non_terminals(), cnf.get_user_non_terminals());
+        assert!(read.to_cnf().get_user_rules().is_empty());
+        assert_eq!(cnf.words_up_to(3), Vec::<String>::new());
+        assert_same_language(&grammar, &cnf, &["", "a", "ba", "baba"]);
+    }
+
+    #[test]
+    fn epsilon_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("SABC\nabc\nS->ABC\nA->aA\nA->\nB->b\nB->C\nC->c\nC->\nS")
+                .expect("Failed to parse the grammar.");
+        let result = grammar.remove_epsilon();
+        assert_eq!(
+            result
+                .get_user_rules()
+                .iter()
+                .filter(|rule| rule.1.is_empty())
+                .count(),
+            1
+        );
+        assert_same_language(
+            &grammar,
+            &result,
+            &["", "a", "aab", "ac", "bc", "aaacc", "ba", "ca"],
+        );
+    }
+
+    #[test]
+    fn epsilon_unit_test_2() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        let result = grammar.remove_epsilon();
+        let start = result.get_user_start();
+        assert!(result
+            .get_user_rules()
+            .iter()
+            .all(|rule| !rule.1.contains(start) && (!rule.1.is_empty() || rule.0 == start)));
+        assert_same_language(&grammar, &result, &["", "ab", "aabb", "aab", "ba", "abab"]);
+    }
+
+    #[test]
+    fn left_factor_unit_test_1() {
+        let grammar = CFGrammar::from_str("SA\nabcde\nS->abcA\nS->abdA\nS->abd\nS->e\nA->a\nS")
+            .expect("Failed to parse the grammar.");
+        let result = grammar.left_factor();
+
+        for rules_right in get_rules_index(&result.get_user_rules()).values() {
+            for (i, rule_right) in rules_right.iter().enumerate() {
+                assert!(rules_right[i + 1..]
+                    .iter()
+                    .all(|other| other.chars().next() != rule_right.chars().next()));
+            }
+        }
+
+        assert_same_language(
+            &grammar,
+            &result,
+            &["abca", "abda", "abd", "e", "ab", "abc", "abcd"],
+        );
+        assert_eq!(
+            result.left_factor().get_user_rules(),
+            result.get_user_rules()
+        );
+    }
+
+    #[test]
+    fn left_recursion_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE")
+                .expect("Failed to parse the grammar.");
+        let result = grammar.eliminate_left_recursion();
+        assert!(grammar.is_left_recursive());
+        assert!(!result.is_left_recursive());
+        assert_same_language(
+            &grammar,
+            &result,
+            &["a", "a+a", "(a+a)*a", "a*(a)", "a+", "()", "", "a**a"],
+        );
+    }
+
+    #[test]
+    fn left_recursion_unit_test_2() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nS->\nA->Sa\nA->b\nB->Bb\nB->A\nS")
+            .expect("Failed to parse the grammar.");
+        let result = grammar.eliminate_left_recursion();
+        assert!(grammar.is_left_recursive());
+        assert!(!result.is_left_recursive());
+        assert_same_language(
+            &grammar,
+            &result,
+            &["", "ab", "bb", "aab", "bbb", "abab", "ba", "bab", "abb"],
+        );
+    }
+
+    #[test]
+    fn union_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->ab\nS").expect("Failed to parse the grammar.");
+        let other =
+            CFGrammar::from_str("Sa\nc\nS->cS\nS->\nS").expect("Failed to parse the grammar.");
+        let union = grammar.union(&other);
+        let mut parser = EarleyParser::new();
+        parser.fit(&union).expect("Fit unsuccessful");
+
+        for word in ["", "ab", "aabb", "c", "ccc"] {
+            assert!(Parser::predict(&mut parser, word));
+        }
+
+        for word in ["a", "abc", "cab", "ba"] {
+            assert!(!Parser::predict(&mut parser, word));
+        }
+    }
+
+    #[test]
+    fn concat_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->ab\nS").expect("Failed to parse the grammar.");
+        let other =
+            CFGrammar::from_str("Sa\nc\nS->cS\nS->\nS").expect("Failed to parse the grammar.");
+        let concat = grammar.concat(&other);
+        let mut parser = EarleyParser::new();
+        parser.fit(&concat).expect("Fit unsuccessful");
+
+        for word in ["ab", "aabbc", "abccc"] {
+            assert!(Parser::predict(&mut parser, word));
+        }
+
+        for word in ["", "c", "cab", "abcab"] {
+            assert!(!Parser::predict(&mut parser, word));
+        }
+    }
+
+    #[test]
+    fn reverse_unit_test_1() {
+        let grammar = get_test_grammar();
+        let reversed = grammar.reverse();
+        assert_eq!(reversed.reverse(), grammar);
+        assert_ne!(reversed, grammar);
+
+        let mut parser = EarleyParser::new();
+        let mut reversed_parser = EarleyParser::new();
+        parser.fit(&grammar).expect("Fit unsuccessful");
+        reversed_parser.fit(&reversed).expect("Fit unsuccessful");
+
+        for word in ["a", "a+a*a", "(a+a)*a", "a*(a", "(a))", "+a", ""] {
+            let reversed_word: String = word.chars().rev().collect();
+            assert_eq!(
+                Parser::predict(&mut parser, word),
+                Parser::predict(&mut reversed_parser, &reversed_word),
+                "{word}"
+            );
+        }
+
+        assert!(Parser::predict(&mut reversed_parser, "a*)a+a("));
+        assert!(!Parser::predict(&mut reversed_parser, "(a+a)*a"));
+    }
+
+    #[test]
+    fn gnf_unit_test_1() {
+        let grammar =
+            CFGrammar::from_str("ETF\na+*()\nE->E+T\nE->T\nT->T*F\nT->F\nF->(E)\nF->a\nE")
+                .expect("Failed to parse the grammar.");
+        let gnf = grammar.to_gnf().expect("Conversion unsuccessful");
+        assert!(is_gnf(&gnf));
+        assert_same_language(
+            &grammar,
+            &gnf,
+            &["a", "(a+a)*a", "a*(a)", "a+", "()", "", "a**a"],
+        );
+    }
+
+    #[test]
+    fn gnf_unit_test_2() {
+        let grammar = CFGrammar::from_str("SAB\nab\nS->AB\nA->Aa\nA->b\nB->SA\nB->a\nS")
+            .expect("Failed to parse the grammar.");
+        let gnf = grammar.to_gnf().expect("Conversion unsuccessful");
+        assert!(is_gnf(&gnf));
+        assert_same_language(
+            &grammar,
+            &gnf,
+            &["ba", "baa", "bbab", "bbaba", "ab", "b", "bab"],
+        );
+    }
+
+    #[test]
+    fn gnf_should_fail_1() {
+        let grammar =
+            CFGrammar::from_str("S\nab\nS->aSb\nS->\nS").expect("Failed to parse the grammar.");
+        assert!(grammar.to_gnf().is_err());
+    }
+
+    fn is_gnf(grammar: &CFGrammar) -> bool {
+        grammar.get_user_rules().iter().all(|(_, rule_right)| {
+            let mut symbols = rule_right.chars();
+            symbols
+                .next()
+                .is_some_and(|symbol| grammar.is_terminal(&symbol))
+                && symbols.all(|symbol| grammar.is_non_terminal(&symbol))
+        })
+    }
+
+    fn is_cnf(grammar: &CFGrammar) -> bool {
+        let start = grammar.get_user_start();
+
+        grammar
+            .get_user_rules()
+            .iter()
+            .all(|(rule_left, rule_right)| {
+                let symbols: Vec<_> = rule_right.chars().collect();
+
+                match symbols.len() {
+                    0 => *rule_left == start,
+                    1 => grammar.is_terminal(&symbols[0]),
+                    2 => symbols
+                        .iter()
+                        .all(|symbol| grammar.is_non_terminal(symbol) && *symbol != start),
+                    _ => false,
+                }
+            })
+    }
+
+    fn assert_same_language(grammar: &CFGrammar, other: &CFGrammar, words: &[&str]) {
+        let mut parser = EarleyParser::new();
+        let mut other_parser = EarleyParser::new();
+        parser.fit(grammar).expect("Fit unsuccessful");
+        other_parser.fit(other).expect("Fit unsuccessful");
+
+        for word in words {
+            assert_eq!(
+                Parser::predict(&mut parser, word),
+                Parser::predict(&mut other_parser, word),
+                "{word}"
+            );
+        }
+    }
+}
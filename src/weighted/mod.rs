@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use super::*;
+
+/// Maximal difference between one and the sum of the probabilities of the rules
+/// of a non-terminal.
+const WEIGHT_TOLERANCE: f64 = 1e-6;
+
+/// Context-free grammar with a probability for every rule.
+#[derive(Debug, Clone)]
+pub struct WeightedGrammar {
+    grammar: CFGrammar,
+    weights: HashMap<CFRule, f64>,
+}
+
+impl WeightedGrammar {
+    pub fn new(grammar: &CFGrammar, weights: &HashMap<CFRule, f64>) -> Self {
+        Self {
+            grammar: grammar.clone(),
+            weights: weights.clone(),
+        }
+    }
+
+    pub fn grammar(&self) -> &CFGrammar {
+        &self.grammar
+    }
+
+    /// Probability of the rule, the rule of the start non-terminal always has one.
+    pub fn weight(&self, rule: &CFRule) -> f64 {
+        if rule.0 == START_RULE {
+            return 1.0;
+        }
+
+        self.weights.get(rule).copied().unwrap_or_default()
+    }
+
+    /// Check that every rule has a probability and that the probabilities of the
+    /// rules of every non-terminal sum up to one.
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        let mut sums = HashMap::<char, f64>::new();
+
+        for (rule, weight) in self.weights.iter() {
+            if !self
+                .grammar
+                .rules
+                .get_vec(&rule.0)
+                .is_some_and(|rules| rules.contains(&rule.1))
+            {
+                bail!(
+                    "The weighted rule {} -> {} is not in the grammar.",
+                    rule.0,
+                    rule.1
+                );
+            }
+
+            if !(0.0..=1.0).contains(weight) {
+                bail!(
+                    "The weight of the rule {} -> {} is not a probability.",
+                    rule.0,
+                    rule.1
+                );
+            }
+
+            *sums.entry(rule.0).or_default() += weight;
+        }
+
+        for (rule_left, rule_right) in self.grammar.rules.flat_iter() {
+            if *rule_left != START_RULE
+                && !self.weights.contains_key(&(*rule_left, rule_right.clone()))
+            {
+                bail!("The rule {} -> {} has no weight.", rule_left, rule_right);
+            }
+        }
+
+        for (rule_left, sum) in sums {
+            if (sum - 1.0).abs() > WEIGHT_TOLERANCE {
+                bail!("The weights of the rules of {rule_left} sum up to {sum} instead of 1.");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_unit_test_1() {
+        let grammar = get_test_grammar();
+        let weights = HashMap::from([
+            (('S', "SS".to_string()), 0.4),
+            (('S', "a".to_string()), 0.6),
+        ]);
+        let weighted = WeightedGrammar::new(&grammar, &weights);
+        assert!(weighted.validate().is_ok());
+        assert_eq!(weighted.weight(&('S', "a".to_string())), 0.6);
+        assert_eq!(weighted.weight(&(START_RULE, "S".to_string())), 1.0);
+    }
+
+    #[test]
+    fn weighted_should_fail_1() {
+        let grammar = get_test_grammar();
+        let invalid = [
+            vec![
+                (('S', "SS".to_string()), 0.5),
+                (('S', "a".to_string()), 0.6),
+            ],
+            vec![(('S', "a".to_string()), 1.0)],
+            vec![
+                (('S', "SS".to_string()), -0.5),
+                (('S', "a".to_string()), 1.5),
+            ],
+            vec![
+                (('S', "SS".to_string()), 0.5),
+                (('S', "a".to_string()), 0.5),
+                (('S', "b".to_string()), 0.0),
+            ],
+        ];
+
+        for weights in invalid {
+            let weights = HashMap::from_iter(weights);
+            assert!(WeightedGrammar::new(&grammar, &weights).validate().is_err());
+        }
+    }
+
+    fn get_test_grammar() -> CFGrammar {
+        CFGrammar::from_str("S\na\nS->SS\nS->a\nS").expect("Failed to parse the grammar.")
+    }
+}